@@ -0,0 +1,102 @@
+// Procedural macros for distribution_select. The weights are checked and summed while the
+// macro expands, so a table built this way needs no work at startup.
+
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{parse::{Parse, ParseStream}, punctuated::Punctuated, Expr, ExprLit, ExprUnary, Lit, Token, UnOp};
+
+struct Entry {
+    value: Expr,
+    weight: Expr
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let value = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let weight = input.parse()?;
+        Ok(Entry {value, weight})
+    }
+}
+
+/// Builds a `distribution_select::StaticTable` from `value => weight` pairs, as in
+/// `static_distribution!["sword" => 1.0, "shield" => 2.5]`. Every weight must be a positive
+/// numeric literal large enough to raise the running total, so the cumulative weights strictly
+/// increase, and the values must be distinct constant expressions. The result can initialize
+/// a `static` or `const`.
+#[proc_macro]
+pub fn static_distribution(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input.into()).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let entries = syn::parse::Parser::parse2(Punctuated::<Entry, Token![,]>::parse_terminated, input.clone())?;
+    if entries.is_empty() {
+        return Err(syn::Error::new_spanned(input, "a static distribution needs at least one value"));
+    }
+    let mut total = 0.0;
+    let mut ends = Vec::new();
+    // Values are compared by their tokens, which catches the same literal or path written twice.
+    let mut seen = Vec::new();
+    for entry in entries.iter() {
+        let value = entry.value.to_token_stream().to_string();
+        if seen.contains(&value) {
+            return Err(syn::Error::new_spanned(&entry.value, "value listed more than once"));
+        }
+        seen.push(value);
+        let weight = literal_weight(&entry.weight)?;
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(syn::Error::new_spanned(&entry.weight, "weights must be positive and finite"));
+        }
+        let next = total + weight;
+        if !next.is_finite() {
+            return Err(syn::Error::new_spanned(&entry.weight, "the total weight overflows f64"));
+        }
+        if next == total {
+            return Err(syn::Error::new_spanned(&entry.weight, "weight too small relative to preceding total"));
+        }
+        total = next;
+        ends.push(Literal::f64_suffixed(total));
+    }
+    let values = entries.iter().map(|entry| &entry.value);
+    Ok(quote! {
+        ::distribution_select::StaticTable::from_cumulative(&[#(#values),*], &[#(#ends),*])
+    })
+}
+
+fn literal_weight(weight: &Expr) -> syn::Result<f64> {
+    match weight {
+        Expr::Lit(ExprLit {lit: Lit::Float(lit), ..}) => lit.base10_parse(),
+        Expr::Lit(ExprLit {lit: Lit::Int(lit), ..}) => lit.base10_parse::<u64>().map(|w| w as f64),
+        Expr::Unary(ExprUnary {op: UnOp::Neg(_), expr, ..}) => literal_weight(expr).map(|w| -w),
+        _ => Err(syn::Error::new_spanned(weight, "weights must be numeric literals"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use crate::expand;
+
+    fn error_of(input: proc_macro2::TokenStream) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_cumulative_weights() {
+        let expanded = expand(quote! {"a" => 1, "b" => 2.5, "c" => 0.5,}).unwrap().to_string();
+        assert!(expanded.contains("StaticTable :: from_cumulative"));
+        assert!(expanded.contains("[1f64 , 3.5f64 , 4f64]"));
+    }
+
+    #[test]
+    fn test_rejects_bad_weights() {
+        assert_eq!(error_of(quote! {"a" => 0.0}), "weights must be positive and finite");
+        assert_eq!(error_of(quote! {"a" => 1.0, "b" => -2}), "weights must be positive and finite");
+        assert_eq!(error_of(quote! {"a" => WEIGHT}), "weights must be numeric literals");
+        assert_eq!(error_of(quote! {}), "a static distribution needs at least one value");
+        assert!(expand(quote! {"a" 1.0}).is_err());
+        assert_eq!(error_of(quote! {"a" => 1e20, "b" => 1}), "weight too small relative to preceding total");
+        assert_eq!(error_of(quote! {Rarity::Common => 1, "b" => 2, Rarity::Common => 3}), "value listed more than once");
+    }
+}
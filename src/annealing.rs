@@ -0,0 +1,146 @@
+// At temperature t each weight w counts as w^(1/t): high temperatures flatten the
+// distribution toward uniform, temperatures near zero concentrate it on the heaviest value,
+// and a temperature of one leaves it unchanged.
+
+use std::fmt::Debug;
+use rand::Rng;
+use crate::{pick_weighted, Distribution};
+
+/// Maps a step count, starting from zero, to a positive temperature.
+pub trait Schedule {
+    fn temperature(&self, step: u64) -> f64;
+}
+
+/// Multiplies the temperature by `rate` every step.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExponentialSchedule {
+    pub initial: f64,
+    pub rate: f64
+}
+
+impl Schedule for ExponentialSchedule {
+    fn temperature(&self, step: u64) -> f64 {
+        self.initial * self.rate.powf(step as f64)
+    }
+}
+
+/// Moves from `initial` to `last` in equal increments over `steps`, then stays at `last`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LinearSchedule {
+    pub initial: f64,
+    pub last: f64,
+    pub steps: u64
+}
+
+impl Schedule for LinearSchedule {
+    fn temperature(&self, step: u64) -> f64 {
+        if step >= self.steps {
+            self.last
+        } else {
+            self.initial + (self.last - self.initial) * step as f64 / self.steps as f64
+        }
+    }
+}
+
+impl <F:Fn(u64)->f64> Schedule for F {
+    fn temperature(&self, step: u64) -> f64 {
+        self(step)
+    }
+}
+
+pub(crate) fn pick_tempered<'a, T, I, R>(weighted: I, temperature: f64, rng: &mut R) -> Option<&'a T>
+    where T: 'a, I: Iterator<Item=(&'a T, f64)> + Clone, R: Rng + ?Sized {
+    assert!(temperature > 0.0);
+    let max_log = weighted.clone().map(|(_, weight)| weight.ln()).fold(f64::NEG_INFINITY, f64::max);
+    pick_weighted(weighted.map(|(value, weight)| (value, ((weight.ln() - max_log) / temperature).exp())), rng)
+}
+
+/// A distribution picked at a temperature that advances along a schedule with every pick.
+#[derive(Clone, Debug)]
+pub struct Annealed<T, S> {
+    dist: Distribution<T>,
+    schedule: S,
+    step: u64
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, S:Schedule> Annealed<T, S> {
+    pub fn new(dist: Distribution<T>, schedule: S) -> Self {
+        Annealed {dist, schedule, step: 0}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    /// The temperature the next pick will use.
+    pub fn temperature(&self) -> f64 {
+        self.schedule.temperature(self.step)
+    }
+
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+
+    /// The weight `value` is picked with at the current temperature, scaled as in
+    /// `pick_tempered()` so that the heaviest value has weight one.
+    pub(crate) fn tempered_weight(&self, value: &T) -> Option<f64> {
+        let weight = self.dist.get(value)?;
+        let max_log = self.dist.weights().map(f64::ln).fold(f64::NEG_INFINITY, f64::max);
+        Some(((weight.ln() - max_log) / self.temperature()).exp()).filter(|weight| *weight > 0.0)
+    }
+
+    pub fn pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        assert!(!self.dist.is_empty());
+        let picked = pick_tempered(self.dist.originals.iter().map(|(value, weight)| (value, *weight)),
+                                   self.temperature(), rng).unwrap();
+        self.step += 1;
+        self.dist.notify_pick(picked).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Annealed, Distribution, ExponentialSchedule, LinearSchedule, Schedule, SplitMix64};
+
+    #[test]
+    fn test_schedules() {
+        let exponential = ExponentialSchedule {initial: 8.0, rate: 0.5};
+        assert_eq!(exponential.temperature(0), 8.0);
+        assert_eq!(exponential.temperature(3), 1.0);
+        let linear = LinearSchedule {initial: 2.0, last: 1.0, steps: 4};
+        assert_eq!(linear.temperature(2), 1.5);
+        assert_eq!(linear.temperature(100), 1.0);
+        assert_eq!((|step: u64| 1.0 / (step + 1) as f64).temperature(3), 0.25);
+    }
+
+    #[test]
+    fn test_annealed_cools_toward_heaviest() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 2.0);
+        let mut annealed = Annealed::new(dist, ExponentialSchedule {initial: 1.0, rate: 0.9});
+        for _ in 0..100 {
+            annealed.pick();
+        }
+        assert_eq!(annealed.step(), 100);
+        assert!(annealed.temperature() < 1e-4);
+        assert!((0..100).all(|_| annealed.pick() == 'b'));
+        annealed.reset();
+        assert_eq!(annealed.temperature(), 1.0);
+
+        let mut seeded = |seed| {
+            annealed.reset();
+            let mut rng = SplitMix64::new(seed);
+            (0..30).map(|_| annealed.pick_with(&mut rng)).collect::<String>()
+        };
+        assert_eq!(seeded(6), seeded(6));
+    }
+}
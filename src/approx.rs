@@ -0,0 +1,123 @@
+use std::{fmt::Debug, collections::BTreeSet, hash::{Hash, Hasher}};
+use rand::Rng;
+use crate::{hashing::StableHasher, pick_weighted, Distribution};
+
+/// Keeps weights in a count-min sketch, so memory stays fixed no matter how many distinct values
+/// are added. Only the heaviest `max_candidates` values seen so far are eligible to be picked,
+/// and each is picked in proportion to its (over-)estimated weight.
+#[derive(Clone, Debug)]
+pub struct ApproxDistribution<T> {
+    width: usize,
+    depth: usize,
+    sketch: Vec<f64>,
+    candidates: BTreeSet<T>,
+    max_candidates: usize,
+    total_weight: f64
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Hash> ApproxDistribution<T> {
+    pub fn new(width: usize, depth: usize, max_candidates: usize) -> Self {
+        assert!(width > 0 && depth > 0 && max_candidates > 0);
+        ApproxDistribution {width, depth, sketch: vec![0.0; width * depth], candidates: BTreeSet::new(), max_candidates, total_weight: 0.0}
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_weight == 0.0
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    pub fn add(&mut self, value: &T, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        for row in 0..self.depth {
+            let cell = self.cell(row, value);
+            self.sketch[cell] += weight;
+        }
+        self.total_weight += weight;
+
+        if self.candidates.contains(value) {
+            return;
+        }
+        if self.candidates.len() < self.max_candidates {
+            self.candidates.insert(value.clone());
+        } else {
+            let (lightest, lightest_weight) = self.candidates.iter()
+                .map(|c| (c, self.estimate(c)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            if self.estimate(value) > lightest_weight {
+                let lightest = lightest.clone();
+                self.candidates.remove(&lightest);
+                self.candidates.insert(value.clone());
+            }
+        }
+    }
+
+    /// Never underestimates the total weight added for `value`.
+    pub fn estimate(&self, value: &T) -> f64 {
+        (0..self.depth)
+            .map(|row| self.sketch[self.cell(row, value)])
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn candidates(&self) -> impl Iterator<Item=&T> {
+        self.candidates.iter()
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        assert!(!self.is_empty());
+        pick_weighted(self.candidates.iter().map(|c| (c, self.estimate(c))), rng).unwrap().clone()
+    }
+
+    pub fn to_distribution(&self) -> Distribution<T> {
+        let mut result = Distribution::new();
+        for candidate in self.candidates.iter() {
+            result.add(candidate, self.estimate(candidate));
+        }
+        result
+    }
+
+    // Hashed with StableHasher, so a sketch's layout is the same on every run and platform.
+    fn cell(&self, row: usize, value: &T) -> usize {
+        let mut hasher = StableHasher::new();
+        (row as u64).hash(&mut hasher);
+        value.hash(&mut hasher);
+        row * self.width + (hasher.finish() % self.width as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ApproxDistribution, SplitMix64};
+
+    #[test]
+    fn test_estimates_and_candidates() {
+        let mut dist = ApproxDistribution::new(64, 4, 2);
+        for i in 0..200u32 {
+            dist.add(&i, 1.0);
+        }
+        dist.add(&1000, 500.0);
+        dist.add(&2000, 300.0);
+        assert_eq!(dist.total_weight(), 1000.0);
+        assert!(dist.estimate(&1000) >= 500.0);
+        assert!(dist.estimate(&7) >= 1.0);
+        assert_eq!(dist.candidates().copied().collect::<Vec<_>>(), vec![1000, 2000]);
+
+        let picks: Vec<u32> = (0..500).map(|_| dist.random_pick()).collect();
+        assert!(picks.iter().all(|p| *p == 1000 || *p == 2000));
+        assert!(picks.iter().filter(|p| **p == 1000).count() > 250);
+        assert_eq!(dist.to_distribution().originals.len(), 2);
+
+        let seeded = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..20).map(|_| dist.pick_with(&mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(seeded(3), seeded(3));
+    }
+}
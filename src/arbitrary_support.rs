@@ -0,0 +1,87 @@
+use std::fmt::Debug;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use crate::Distribution;
+
+// Fuzzed weights outside (0, MAX_FUZZ_WEIGHT] are mapped back into it, so that every input
+// decodes to a valid distribution and totals cannot overflow.
+const MAX_FUZZ_WEIGHT: f64 = 1e12;
+
+fn fuzz_weight(raw: f64) -> f64 {
+    if raw.is_finite() && raw > 0.0 {raw.min(MAX_FUZZ_WEIGHT)} else {1.0}
+}
+
+impl <'a, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Arbitrary<'a>> Arbitrary<'a> for Distribution<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut result = Distribution::new();
+        for entry in u.arbitrary_iter::<(T, f64)>()? {
+            let (value, weight) = entry?;
+            result.add(&value, fuzz_weight(weight));
+        }
+        Ok(result)
+    }
+}
+
+/// One step of a fuzzed sequence of operations on a distribution.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DistributionOp<T> {
+    Add(T, f64),
+    Remove(T),
+    Keep(T),
+    Pick
+}
+
+impl <'a, T:Arbitrary<'a>> Arbitrary<'a> for DistributionOp<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=3u8)? {
+            0 => DistributionOp::Add(T::arbitrary(u)?, fuzz_weight(f64::arbitrary(u)?)),
+            1 => DistributionOp::Remove(T::arbitrary(u)?),
+            2 => DistributionOp::Keep(T::arbitrary(u)?),
+            _ => DistributionOp::Pick
+        })
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> DistributionOp<T> {
+    /// Applies this operation; `Keep(v)` replaces `dist` with `dist.distro_with(|x| x <= v)`,
+    /// and `Pick` is skipped on an empty distribution.
+    pub fn apply(&self, dist: &mut Distribution<T>) {
+        match self {
+            DistributionOp::Add(value, weight) => dist.add(value, *weight),
+            DistributionOp::Remove(value) => {
+                dist.remove(value);
+            }
+            DistributionOp::Keep(bound) => *dist = dist.distro_with(|value| value <= bound),
+            DistributionOp::Pick => {
+                if !dist.is_empty() {
+                    dist.random_pick();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::RngCore;
+    use crate::{Distribution, DistributionOp, SplitMix64};
+
+    #[test]
+    fn test_fuzzed_sequences_hold_invariants() {
+        let mut bytes = vec![0u8; 4096];
+        SplitMix64::new(7).fill_bytes(&mut bytes);
+        let mut u = Unstructured::new(&bytes);
+        let mut dist = Distribution::<u8>::arbitrary(&mut u).unwrap();
+        let mut steps = 0;
+        while !u.is_empty() {
+            DistributionOp::arbitrary(&mut u).unwrap().apply(&mut dist);
+            steps += 1;
+            let sum: f64 = dist.originals.values().sum();
+            assert!((dist.total_weight - sum).abs() <= 1e-9 * sum.max(1.0));
+            // Every stored weight has exactly one entry in the cumulative table.
+            assert_eq!(dist.values.len(), dist.originals.len());
+            assert!(dist.values.iter().all(|value| dist.originals.contains_key(value)));
+        }
+        assert!(steps > 100);
+    }
+}
@@ -0,0 +1,118 @@
+// Exact distributions of quantities computed from independent picks, found by combining the
+// weight tables directly rather than by simulation, along with pointwise arithmetic on weights.
+
+use std::{fmt::Debug, collections::BTreeSet};
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// The distribution of the largest of `n` independent picks, with weights that are
+    /// probabilities. The chance that the maximum is at most `x` is the chance that one pick
+    /// is at most `x`, raised to the power `n`.
+    pub fn max_of(&self, n: u32) -> Distribution<T> {
+        order_statistic(self.originals.iter(), n)
+    }
+
+    /// The distribution of the smallest of `n` independent picks, found like `max_of()` from
+    /// the chance that one pick is at least `x`.
+    pub fn min_of(&self, n: u32) -> Distribution<T> {
+        order_statistic(self.originals.iter().rev(), n)
+    }
+
+    /// A distribution over the values of either table, weighting each by `f(mine, theirs)`,
+    /// where a value missing from one table counts as weight zero there. Values for which `f`
+    /// gives zero are left out; negative or non-finite results are rejected.
+    pub fn combine<F:Fn(f64, f64)->f64>(&self, other: &Distribution<T>, f: F) -> Distribution<T> {
+        let values: BTreeSet<&T> = self.originals.keys().chain(other.originals.keys()).collect();
+        let mut result = Distribution::new();
+        for value in values {
+            let weight = f(self.get(value).unwrap_or(0.0), other.get(value).unwrap_or(0.0));
+            assert!(weight.is_finite() && weight >= 0.0);
+            if weight > 0.0 {
+                result.add(value, weight);
+            }
+        }
+        result
+    }
+}
+
+impl Distribution<i64> {
+    /// The distribution of the sum of independent picks from this and `other`. Probabilities
+    /// multiply as in `product()`, so the weights sum to one, less any pairs whose weight
+    /// underflows to zero.
+    pub fn convolve(&self, other: &Distribution<i64>) -> Distribution<i64> {
+        self.product(other).group_by(|(a, b)| a.checked_add(*b).expect("sum overflows i64"))
+    }
+}
+
+// Walks the values from the extreme that is least likely to win, so each value's probability
+// is the step in the n-th power of the running cumulative probability.
+fn order_statistic<'a, T:'a + Clone + PartialEq + Eq + PartialOrd + Ord + Debug, I:Iterator<Item=(&'a T, &'a f64)> + Clone>(values: I, n: u32) -> Distribution<T> {
+    assert!(n > 0);
+    let total: f64 = values.clone().map(|(_, weight)| weight).sum();
+    let mut result = Distribution::new();
+    let mut cumulative = 0.0;
+    let mut previous = 0.0;
+    for (value, weight) in values {
+        cumulative += weight;
+        let current = (cumulative / total).min(1.0).powi(n as i32);
+        if current > previous {
+            result.add(value, current - previous);
+        }
+        previous = current;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Distribution;
+
+    fn die(sides: i64) -> Distribution<i64> {
+        (1..=sides).map(|face| (face, 1.0)).collect::<Vec<_>>().into()
+    }
+
+    #[test]
+    fn test_convolve() {
+        let two_d6 = die(6).convolve(&die(6));
+        assert_eq!(two_d6.keys().copied().collect::<Vec<_>>(), (2..=12).collect::<Vec<_>>());
+        assert!((two_d6.get(&7).unwrap() - 6.0 / 36.0).abs() < 1e-12);
+        assert!((two_d6.get(&2).unwrap() - 1.0 / 36.0).abs() < 1e-12);
+        assert!((two_d6.total_weight - 1.0).abs() < 1e-12);
+
+        let modifier: Distribution<i64> = vec![(3, 1.0)].into();
+        let plus_three = two_d6.convolve(&modifier);
+        assert_eq!(plus_three.keys().next(), Some(&5));
+        assert!((plus_three.get(&10).unwrap() - 6.0 / 36.0).abs() < 1e-12);
+        assert!(Distribution::new().convolve(&die(6)).is_empty());
+    }
+
+    #[test]
+    fn test_max_and_min_of() {
+        let advantage = die(6).max_of(2);
+        assert!((advantage.get(&6).unwrap() - 11.0 / 36.0).abs() < 1e-12);
+        assert!((advantage.get(&1).unwrap() - 1.0 / 36.0).abs() < 1e-12);
+        assert!((advantage.total_weight - 1.0).abs() < 1e-12);
+
+        let disadvantage = die(6).min_of(2);
+        assert!((disadvantage.get(&1).unwrap() - 11.0 / 36.0).abs() < 1e-12);
+        assert!((disadvantage.get(&6).unwrap() - 1.0 / 36.0).abs() < 1e-12);
+
+        let single = die(4).max_of(1);
+        assert!(single.weights().all(|weight| (weight - 0.25).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_combine() {
+        let a: Distribution<char> = vec![('x', 1.0), ('y', 4.0)].into();
+        let b: Distribution<char> = vec![('y', 2.0), ('z', 3.0)].into();
+
+        let average = a.combine(&b, |p, q| (p + q) / 2.0);
+        assert_eq!(average.iter().collect::<Vec<_>>(), vec![(&'x', 0.5), (&'y', 3.0), (&'z', 1.5)]);
+
+        let maxima = a.combine(&b, f64::max);
+        assert_eq!(maxima.weights().collect::<Vec<_>>(), vec![1.0, 4.0, 3.0]);
+
+        let overlap = a.combine(&b, f64::min);
+        assert_eq!(overlap.iter().collect::<Vec<_>>(), vec![(&'y', 2.0)]);
+    }
+}
@@ -0,0 +1,221 @@
+// Treats weights as pseudo-counts of a Dirichlet prior.
+
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::Rng;
+use rand_distr::{Beta, Gamma};
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Replaces the weights with a posterior that blends them with `observed` counts. The
+    /// current weights, normalized, act as a prior worth `prior_strength` observations, so
+    /// a strength of zero keeps only the observed counts. Observed values not yet present
+    /// are added.
+    pub fn posterior_update(&mut self, observed: &BTreeMap<T, u64>, prior_strength: f64) {
+        assert!(prior_strength.is_finite() && prior_strength >= 0.0);
+        let mut posterior: BTreeMap<T, f64> = if self.is_empty() {
+            BTreeMap::new()
+        } else {
+            self.originals.iter().map(|(value, weight)| (value.clone(), prior_strength * weight / self.total_weight)).collect()
+        };
+        for (value, count) in observed.iter() {
+            *posterior.entry(value.clone()).or_insert(0.0) += *count as f64;
+        }
+        posterior.retain(|_, weight| *weight > 0.0);
+        assert!(!posterior.is_empty());
+        self.replace_weights(posterior);
+    }
+
+    /// A random reweighting drawn from a Dirichlet whose mean is this distribution. Larger
+    /// values of `concentration` keep the draw closer to the current weights.
+    pub fn sample_dirichlet(&self, concentration: f64) -> Distribution<T> {
+        self.sample_dirichlet_with(concentration, &mut rand::thread_rng())
+    }
+
+    pub fn sample_dirichlet_with<R:Rng + ?Sized>(&self, concentration: f64, rng: &mut R) -> Distribution<T> {
+        assert!(concentration.is_finite() && concentration > 0.0 && !self.is_empty());
+        let mut result = Distribution::new();
+        for (value, weight) in self.originals.iter() {
+            // A shape that underflows to zero would only ever draw zero.
+            let shape = concentration * (weight / self.total_weight);
+            if shape == 0.0 {
+                continue;
+            }
+            let gamma = Gamma::new(shape, 1.0).expect("a finite positive concentration gives finite positive shapes");
+            let draw = rng.sample(gamma);
+            if draw > 0.0 {
+                result.add(value, draw);
+            }
+        }
+        // Tiny shapes can underflow every draw; the Dirichlet then puts all its mass on one
+        // value, chosen in proportion to the mean.
+        if result.is_empty() {
+            result.add(self.pick_ref(rng), 1.0);
+        }
+        result
+    }
+}
+
+/// Success and failure counts per value, each value starting from a uniform Beta(1, 1) prior.
+#[derive(Clone, Debug)]
+pub struct BetaWeighted<T> {
+    counts: BTreeMap<T, (u64, u64)>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> BetaWeighted<T> {
+    pub fn new() -> Self {
+        BetaWeighted {counts: BTreeMap::new()}
+    }
+
+    /// Makes `value` selectable before anything has been recorded for it.
+    pub fn insert(&mut self, value: &T) {
+        self.counts.entry(value.clone()).or_insert((0, 0));
+    }
+
+    pub fn record(&mut self, value: &T, success: bool) {
+        let (successes, failures) = self.counts.entry(value.clone()).or_insert((0, 0));
+        if success {
+            *successes += 1;
+        } else {
+            *failures += 1;
+        }
+    }
+
+    /// Returns `(successes, failures)`.
+    pub fn counts(&self, value: &T) -> Option<(u64, u64)> {
+        self.counts.get(value).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Weights each value by its posterior mean success rate.
+    pub fn mean_distribution(&self) -> Distribution<T> {
+        let mut result = Distribution::new();
+        for (value, (successes, failures)) in self.counts.iter() {
+            result.add(value, (*successes as f64 + 1.0) / ((successes + failures) as f64 + 2.0));
+        }
+        result
+    }
+
+    /// Weights each value by one draw from its posterior, as in Thompson sampling.
+    pub fn sample_distribution(&self) -> Distribution<T> {
+        self.sample_distribution_with(&mut rand::thread_rng())
+    }
+
+    pub fn sample_distribution_with<R:Rng + ?Sized>(&self, rng: &mut R) -> Distribution<T> {
+        let mut result = Distribution::new();
+        for (value, (successes, failures)) in self.counts.iter() {
+            let beta = Beta::new(*successes as f64 + 1.0, *failures as f64 + 1.0).unwrap();
+            let weight = rng.sample(beta);
+            if weight > 0.0 {
+                result.add(value, weight);
+            }
+        }
+        result
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for BetaWeighted<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+    use crate::{observer::tests::EventLog, BetaWeighted, Distribution};
+
+    #[test]
+    fn test_posterior_update() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 3.0);
+        dist.add(&'b', 1.0);
+        let mut observed = BTreeMap::new();
+        observed.insert('b', 5);
+        observed.insert('c', 2);
+        dist.posterior_update(&observed, 8.0);
+        assert_eq!(dist.originals[&'a'], 6.0);
+        assert_eq!(dist.originals[&'b'], 7.0);
+        assert_eq!(dist.originals[&'c'], 2.0);
+        assert_eq!(dist.total_weight, 15.0);
+
+        dist.posterior_update(&observed, 0.0);
+        assert!(!dist.originals.contains_key(&'a'));
+        assert_eq!(dist.total_weight, 7.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_posterior_update_rejects_infinite_strength() {
+        let mut dist: Distribution<char> = vec![('a', 1.0)].into();
+        dist.posterior_update(&BTreeMap::new(), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_posterior_update_notifies() {
+        let log = Arc::new(EventLog::default());
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 1.0);
+        dist.set_observer(log.clone());
+        let mut observed = BTreeMap::new();
+        observed.insert('b', 1);
+        observed.insert('c', 2);
+        dist.posterior_update(&observed, 0.0);
+        assert_eq!(log.events(), vec!["remove a 1", "add c 2"]);
+    }
+
+    #[test]
+    fn test_sample_dirichlet() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 3.0);
+        let trials = 2000;
+        let mean_b: f64 = (0..trials).map(|_| {
+            let draw = dist.sample_dirichlet(40.0);
+            draw.originals.get(&'b').copied().unwrap_or(0.0) / draw.total_weight
+        }).sum::<f64>() / trials as f64;
+        assert!((mean_b - 0.75).abs() < 0.01);
+
+        let sparse = dist.sample_dirichlet(1e-300);
+        assert_eq!(sparse.originals.len(), 1);
+        let underflowed = dist.sample_dirichlet(f64::MIN_POSITIVE * f64::EPSILON);
+        assert_eq!(underflowed.originals.len(), 1);
+        let huge = dist.sample_dirichlet(1e300);
+        assert!(huge.total_weight.is_finite());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_dirichlet_rejects_infinite_concentration() {
+        let dist: Distribution<char> = vec![('a', 1.0)].into();
+        dist.sample_dirichlet(f64::INFINITY);
+    }
+
+    #[test]
+    fn test_beta_weighted() {
+        let mut arms = BetaWeighted::new();
+        arms.insert(&"new");
+        for _ in 0..8 {
+            arms.record(&"good", true);
+        }
+        arms.record(&"good", false);
+        arms.record(&"bad", false);
+        assert_eq!(arms.counts(&"good"), Some((8, 1)));
+        assert_eq!(arms.len(), 3);
+
+        let means = arms.mean_distribution();
+        assert_eq!(means.originals[&"good"], 9.0 / 11.0);
+        assert_eq!(means.originals[&"bad"], 1.0 / 3.0);
+        assert_eq!(means.originals[&"new"], 0.5);
+
+        let sampled = arms.sample_distribution();
+        assert!(sampled.originals.values().all(|weight| *weight > 0.0 && *weight <= 1.0));
+    }
+}
@@ -0,0 +1,160 @@
+// Histogram buckets over a numeric quantity `f(value)`. Each bucket's weight is the summed
+// weight of the values falling in it, so a binned distribution picks buckets with the same
+// probabilities as picking a value and then finding its bucket.
+
+use std::fmt::Debug;
+use ordered_float::OrderedFloat;
+use crate::Distribution;
+
+/// The half-open interval `[lower, upper)` covered by one histogram bucket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bin {
+    lower: OrderedFloat<f64>,
+    upper: OrderedFloat<f64>
+}
+
+impl Bin {
+    pub fn new(lower: f64, upper: f64) -> Self {
+        assert!(lower.is_finite() && upper.is_finite() && lower < upper);
+        Bin {lower: OrderedFloat(lower), upper: OrderedFloat(upper)}
+    }
+
+    pub fn lower(&self) -> f64 {
+        self.lower.into_inner()
+    }
+
+    pub fn upper(&self) -> f64 {
+        self.upper.into_inner()
+    }
+
+    pub fn width(&self) -> f64 {
+        self.upper() - self.lower()
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.lower() <= x && x < self.upper()
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Buckets of width `bin_width` aligned to multiples of it, so `f(value) = 7.5` with a
+    /// width of 5 lands in `[5, 10)`. Only buckets holding at least one value appear. Panics if
+    /// some `f(value)` is so far from zero, relative to `bin_width`, that its bucket's bounds
+    /// round to the same `f64`.
+    pub fn binned<F:Fn(&T)->f64>(&self, bin_width: f64, f: F) -> Distribution<Bin> {
+        assert!(bin_width.is_finite() && bin_width > 0.0);
+        self.group_by(|value| {
+            let x = f(value);
+            assert!(x.is_finite());
+            let index = (x / bin_width).floor();
+            let (lower, upper) = (index * bin_width, (index + 1.0) * bin_width);
+            assert!(lower < upper && upper.is_finite(), "{x} is too far from zero for bins of width {bin_width}");
+            Bin::new(lower, upper)
+        })
+    }
+
+    /// Buckets between consecutive `edges`, which must be strictly ascending. Values whose
+    /// `f(value)` falls outside `[edges[0], edges[last])` are left out, and buckets holding
+    /// no values do not appear.
+    pub fn binned_edges<F:Fn(&T)->f64>(&self, edges: &[f64], f: F) -> Distribution<Bin> {
+        assert!(edges.len() >= 2);
+        let bins: Vec<Bin> = edges.windows(2).map(|pair| Bin::new(pair[0], pair[1])).collect();
+        let mut result = Distribution::new();
+        for (bin, weight) in self.group_by(|value| {
+            let x = f(value);
+            let index = edges.partition_point(|edge| *edge <= x);
+            (index >= 1 && index < edges.len()).then(|| bins[index - 1])
+        }).iter() {
+            if let Some(bin) = bin {
+                result.add(bin, weight);
+            }
+        }
+        result
+    }
+
+    /// Splits the values, in order, into at most `k` runs of roughly equal total weight. Each
+    /// run appears as its `(first, last)` values with the run's summed weight. A value goes to
+    /// the run containing the midpoint of its probability mass, so a single heavy value can
+    /// take the place of several runs and leave fewer than `k`.
+    pub fn binned_quantiles(&self, k: usize) -> Distribution<(T, T)> {
+        assert!(k > 0);
+        let mut runs: Vec<(T, T, f64)> = Vec::new();
+        let mut current = None;
+        let mut cumulative = 0.0;
+        for (value, weight) in self.originals.iter() {
+            let midpoint = (cumulative + weight / 2.0) / self.total_weight;
+            let run = ((midpoint * k as f64) as usize).min(k - 1);
+            cumulative += weight;
+            match runs.last_mut() {
+                Some((_, last, sum)) if current == Some(run) => {
+                    *last = value.clone();
+                    *sum += weight;
+                }
+                _ => {
+                    runs.push((value.clone(), value.clone(), *weight));
+                    current = Some(run);
+                }
+            }
+        }
+        let mut result = Distribution::new();
+        for (first, last, weight) in runs {
+            result.add(&(first, last), weight);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bin, Distribution};
+
+    fn example() -> Distribution<u32> {
+        vec![(1, 1.0), (4, 2.0), (5, 3.0), (9, 4.0), (12, 5.0)].into()
+    }
+
+    #[test]
+    fn test_binned() {
+        let binned = example().binned(5.0, |x| *x as f64);
+        assert_eq!(binned.keys().copied().collect::<Vec<_>>(), vec![Bin::new(0.0, 5.0), Bin::new(5.0, 10.0), Bin::new(10.0, 15.0)]);
+        assert_eq!(binned.weights().collect::<Vec<_>>(), vec![3.0, 7.0, 5.0]);
+        assert_eq!(binned.total_weight, 15.0);
+
+        let negative: Distribution<i32> = vec![(-3, 1.0), (2, 1.0)].into();
+        let binned = negative.binned(2.0, |x| *x as f64);
+        assert_eq!(binned.keys().copied().collect::<Vec<_>>(), vec![Bin::new(-4.0, -2.0), Bin::new(2.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_binned_edges() {
+        let binned = example().binned_edges(&[2.0, 5.0, 6.0, 12.0], |x| *x as f64);
+        assert_eq!(binned.keys().copied().collect::<Vec<_>>(), vec![Bin::new(2.0, 5.0), Bin::new(5.0, 6.0), Bin::new(6.0, 12.0)]);
+        assert_eq!(binned.weights().collect::<Vec<_>>(), vec![2.0, 3.0, 4.0]);
+
+        let bin = binned.random_pick();
+        assert!(bin.contains(bin.lower()) && !bin.contains(bin.upper()));
+        assert!(example().binned_edges(&[20.0, 30.0], |x| *x as f64).is_empty());
+    }
+
+    #[test]
+    fn test_binned_quantiles() {
+        let uniform: Distribution<u32> = (0..12).map(|i| (i, 1.0)).collect::<Vec<_>>().into();
+        let quartiles = uniform.binned_quantiles(4);
+        assert_eq!(quartiles.keys().copied().collect::<Vec<_>>(), vec![(0, 2), (3, 5), (6, 8), (9, 11)]);
+        assert!(quartiles.weights().all(|weight| weight == 3.0));
+
+        let halves = example().binned_quantiles(2);
+        assert_eq!(halves.keys().copied().collect::<Vec<_>>(), vec![(1, 5), (9, 12)]);
+        assert_eq!(halves.weights().collect::<Vec<_>>(), vec![6.0, 9.0]);
+
+        let heavy: Distribution<char> = vec![('a', 1.0), ('b', 100.0), ('c', 1.0)].into();
+        assert_eq!(heavy.binned_quantiles(5).keys().count(), 3);
+        assert_eq!(uniform.binned_quantiles(1).keys().copied().collect::<Vec<_>>(), vec![(0, 11)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "too far from zero for bins of width 1")]
+    fn test_binned_rejects_indistinct_bounds() {
+        let dist: Distribution<u8> = vec![(0, 1.0)].into();
+        dist.binned(1.0, |_| 2f64.powi(53));
+    }
+}
@@ -0,0 +1,272 @@
+// Binary layout (all integers and floats little-endian):
+//   version: u8
+//   count:   u64
+//   count x (weight: f64, value: ByteValue encoding), values in ascending order
+
+use std::{fmt::{self, Debug, Display}, error::Error};
+use crate::{Distribution, ExportOptions};
+
+const FORMAT_VERSION: u8 = 1;
+const CANONICAL_DECIMALS: u32 = 12;
+
+/// Values that can be stored in the binary layout used by `Distribution::to_bytes()`.
+pub trait ByteValue: Sized {
+    fn write_bytes(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value from the front of `bytes`, returning it with the number of bytes consumed.
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, usize), ValueError>;
+}
+
+/// Why `ByteValue::read_bytes()` could not decode a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueError {
+    /// The bytes ended partway through the value.
+    Truncated,
+    /// The bytes are not a valid encoding of the value.
+    Malformed
+}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueError::Truncated => write!(f, "input ended partway through a value"),
+            ValueError::Malformed => write!(f, "malformed value encoding")
+        }
+    }
+}
+
+impl Error for ValueError {}
+
+fn take(bytes: &[u8], len: usize) -> Result<&[u8], ValueError> {
+    bytes.get(..len).ok_or(ValueError::Truncated)
+}
+
+macro_rules! byte_value_for_numbers {
+    ($($t:ty),*) => {
+        $(impl ByteValue for $t {
+            fn write_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_bytes(bytes: &[u8]) -> Result<(Self, usize), ValueError> {
+                const SIZE: usize = std::mem::size_of::<$t>();
+                let raw = take(bytes, SIZE)?;
+                Ok((<$t>::from_le_bytes(raw.try_into().unwrap()), SIZE))
+            }
+        })*
+    }
+}
+
+byte_value_for_numbers!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl ByteValue for bool {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, usize), ValueError> {
+        match take(bytes, 1)?[0] {
+            0 => Ok((false, 1)),
+            1 => Ok((true, 1)),
+            _ => Err(ValueError::Malformed)
+        }
+    }
+}
+
+impl ByteValue for char {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        (*self as u32).write_bytes(out);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, usize), ValueError> {
+        let (code, used) = u32::read_bytes(bytes)?;
+        Ok((char::from_u32(code).ok_or(ValueError::Malformed)?, used))
+    }
+}
+
+impl ByteValue for String {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        (self.len() as u64).write_bytes(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, usize), ValueError> {
+        let (len, used) = u64::read_bytes(bytes)?;
+        // A length beyond the address space cannot fit in the input either.
+        let len = usize::try_from(len).map_err(|_| ValueError::Truncated)?;
+        let text = std::str::from_utf8(take(&bytes[used..], len)?).map_err(|_| ValueError::Malformed)?;
+        Ok((text.to_owned(), used + len))
+    }
+}
+
+impl <A:ByteValue, B:ByteValue> ByteValue for (A, B) {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.0.write_bytes(out);
+        self.1.write_bytes(out);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, usize), ValueError> {
+        let (a, used_a) = A::read_bytes(bytes)?;
+        let (b, used_b) = B::read_bytes(&bytes[used_a..])?;
+        Ok(((a, b), used_a + used_b))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    UnsupportedVersion(u8),
+    Truncated,
+    InvalidValue,
+    InvalidWeight(f64),
+    UnsortedValues,
+    TrailingBytes
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported format version {v}"),
+            DecodeError::Truncated => write!(f, "input ended before the table was complete"),
+            DecodeError::InvalidValue => write!(f, "malformed value encoding"),
+            DecodeError::InvalidWeight(w) => write!(f, "weight {w} is not positive and finite"),
+            DecodeError::UnsortedValues => write!(f, "values are not in strictly ascending order"),
+            DecodeError::TrailingBytes => write!(f, "unexpected bytes after the table")
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl From<ValueError> for DecodeError {
+    fn from(error: ValueError) -> Self {
+        match error {
+            ValueError::Truncated => DecodeError::Truncated,
+            ValueError::Malformed => DecodeError::InvalidValue
+        }
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + ByteValue> Distribution<T> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with(ExportOptions::default())
+    }
+
+    pub fn to_bytes_with(&self, options: ExportOptions) -> Vec<u8> {
+        let weights = self.exported_weights(options);
+        let mut out = vec![FORMAT_VERSION];
+        (weights.len() as u64).write_bytes(&mut out);
+        for (value, weight) in weights {
+            weight.write_bytes(&mut out);
+            value.write_bytes(&mut out);
+        }
+        out
+    }
+
+    /// The `to_bytes()` layout of the probabilities rounded to `CANONICAL_DECIMALS` places, so
+    /// that distributions that differ only in how their weights were scaled give the same bytes.
+    /// The rounding absorbs the last-bit differences scaling leaves, making the result suitable
+    /// for hashing to detect changes. Values with probabilities that round to zero are left out.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        self.normalized().to_bytes_with(ExportOptions::default().with_decimals(CANONICAL_DECIMALS))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (version, mut rest) = bytes.split_first().ok_or(DecodeError::Truncated)?;
+        if *version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(*version));
+        }
+        let (count, used) = u64::read_bytes(rest)?;
+        rest = &rest[used..];
+
+        let mut result = Distribution::new();
+        let mut previous: Option<T> = None;
+        for _ in 0..count {
+            let (weight, used) = f64::read_bytes(rest)?;
+            rest = &rest[used..];
+            if !(weight.is_finite() && weight > 0.0) {
+                return Err(DecodeError::InvalidWeight(weight));
+            }
+            let (value, used) = T::read_bytes(rest)?;
+            rest = &rest[used..];
+            if previous.as_ref().is_some_and(|p| *p >= value) {
+                return Err(DecodeError::UnsortedValues);
+            }
+            result.add(&value, weight);
+            previous = Some(value);
+        }
+
+        if !rest.is_empty() {
+            return Err(DecodeError::TrailingBytes);
+        }
+        result.trace_constructed("bytes");
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ByteValue, Distribution, DecodeError, ExportOptions, ValueError};
+
+    fn example() -> Distribution<String> {
+        let mut dist = Distribution::new();
+        for (value, weight) in [("b", 0.5), ("a", 1.0), ("d", 4.8), ("c", 3.5)] {
+            dist.add(&value.to_string(), weight);
+        }
+        dist
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dist = example();
+        let restored = Distribution::<String>::from_bytes(&dist.to_bytes()).unwrap();
+        assert_eq!(restored.originals, dist.originals);
+        assert_eq!(restored.total_weight, dist.total_weight);
+    }
+
+    #[test]
+    fn test_normalized_export() {
+        let bytes = example().to_bytes_with(ExportOptions::normalized().with_decimals(1));
+        let restored = Distribution::<String>::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.weights().collect::<Vec<_>>(), vec![0.1, 0.1, 0.4, 0.5]);
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let mut scaled = Distribution::new();
+        for (value, weight) in [("d", 48.0), ("c", 35.0), ("a", 10.0), ("b", 5.0)] {
+            scaled.add(&value.to_string(), weight);
+        }
+        assert_eq!(scaled.to_canonical_bytes(), example().to_canonical_bytes());
+        assert_ne!(scaled.to_bytes(), example().to_bytes());
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let bytes = example().to_bytes();
+        assert_eq!(Distribution::<String>::from_bytes(&[]).unwrap_err(), DecodeError::Truncated);
+        assert_eq!(Distribution::<String>::from_bytes(&[7]).unwrap_err(), DecodeError::UnsupportedVersion(7));
+        assert_eq!(Distribution::<String>::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(), DecodeError::Truncated);
+
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert_eq!(Distribution::<String>::from_bytes(&extra).unwrap_err(), DecodeError::TrailingBytes);
+
+        let mut negative = bytes;
+        negative[9..17].copy_from_slice(&(-1.0f64).to_le_bytes());
+        assert_eq!(Distribution::<String>::from_bytes(&negative).unwrap_err(), DecodeError::InvalidWeight(-1.0));
+
+        let mut not_utf8 = example().to_bytes();
+        let last = not_utf8.len() - 1;
+        not_utf8[last] = 0xff;
+        assert_eq!(Distribution::<String>::from_bytes(&not_utf8).unwrap_err(), DecodeError::InvalidValue);
+    }
+
+    #[test]
+    fn test_value_errors() {
+        assert_eq!(u32::read_bytes(&[1, 2, 3]), Err(ValueError::Truncated));
+        assert_eq!(bool::read_bytes(&[2]), Err(ValueError::Malformed));
+        assert_eq!(char::read_bytes(&0xd800u32.to_le_bytes()), Err(ValueError::Malformed));
+        assert_eq!(String::read_bytes(&[5, 0, 0, 0, 0, 0, 0, 0, b'a']), Err(ValueError::Truncated));
+        assert_eq!(<(u8, bool)>::read_bytes(&[7, 1]), Ok(((7, true), 2)));
+    }
+}
@@ -0,0 +1,83 @@
+// Weighted rendezvous hashing: each (key, value) pair gets the score -weight / ln(h), where h is
+// a hash of the pair mapped into (0, 1), and the key goes to the highest-scoring value. Adding or
+// removing a value only moves keys to or from that value.
+
+use std::{fmt::Debug, collections::BTreeMap, hash::{Hash, Hasher}};
+use crate::{hashing::StableHasher, Distribution};
+
+#[derive(Clone, Debug)]
+pub struct ConsistentSelector<T> {
+    weights: BTreeMap<T, f64>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Hash> ConsistentSelector<T> {
+    pub fn new(dist: &Distribution<T>) -> Self {
+        ConsistentSelector {weights: (*dist.originals).clone()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    pub fn add(&mut self, value: &T, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.weights.insert(value.clone(), weight);
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<f64> {
+        self.weights.remove(value)
+    }
+
+    pub fn select<K: Hash + ?Sized>(&self, key: &K) -> Option<&T> {
+        let mut best: Option<(&T, f64)> = None;
+        for (value, weight) in self.weights.iter() {
+            let score = score(key, value, *weight);
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((value, score));
+            }
+        }
+        best.map(|(value, _)| value)
+    }
+}
+
+fn score<K: Hash + ?Sized, T: Hash>(key: &K, value: &T, weight: f64) -> f64 {
+    let mut hasher = StableHasher::new();
+    key.hash(&mut hasher);
+    value.hash(&mut hasher);
+    let h = ((hasher.finish() >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+    -weight / h.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConsistentSelector, Distribution};
+
+    #[test]
+    fn test_minimal_disruption() {
+        let mut dist = Distribution::new();
+        dist.add(&"east", 1.0);
+        dist.add(&"west", 1.0);
+        dist.add(&"north", 2.0);
+        let mut selector = ConsistentSelector::new(&dist);
+        let before: Vec<&str> = (0..2000usize).map(|k| *selector.select(&k).unwrap()).collect();
+        let north = before.iter().filter(|v| **v == "north").count();
+        assert!(north > 850 && north < 1150);
+
+        selector.remove(&"west");
+        for (k, old) in before.iter().enumerate() {
+            let new = *selector.select(&k).unwrap();
+            if *old != "west" {
+                assert_eq!(new, *old);
+            }
+        }
+
+        selector.add(&"south", 1.0);
+        let moved = (0..2000usize).filter(|k| *selector.select(k).unwrap() == "south").count();
+        assert!(moved > 300 && moved < 700);
+        assert_eq!(ConsistentSelector::<u8>::new(&Distribution::new()).select(&1), None);
+    }
+}
@@ -0,0 +1,190 @@
+// Sampling without replacement under constraints. Values are drawn one at a time in
+// proportion to weight from those not yet drawn; a drawn value that breaks a constraint is
+// discarded rather than returned, since it would break it again on every later draw.
+
+use std::{fmt::Debug, collections::{BTreeMap, BTreeSet}};
+use rand::Rng;
+use crate::{pick_weighted, views::pick_filtered, Distribution};
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Up to `n` distinct values, at most `limits[q]` of them in each category `q`, as
+    /// assigned by `quota`. Categories missing from `limits` are unrestricted. Returns fewer
+    /// than `n` values if the limits run out first.
+    pub fn sample_with_quotas<Q:Ord, F:Fn(&T)->Q>(&self, n: usize, quota: F, limits: BTreeMap<Q, usize>) -> Vec<T> {
+        self.sample_with_quotas_with(n, quota, limits, &mut rand::thread_rng())
+    }
+
+    pub fn sample_with_quotas_with<Q:Ord, F:Fn(&T)->Q, R:Rng + ?Sized>(&self, n: usize, quota: F, limits: BTreeMap<Q, usize>, rng: &mut R) -> Vec<T> {
+        let mut remaining = limits;
+        self.sample_distinct(n, rng, |value| match remaining.get_mut(&quota(value)) {
+            Some(0) => false,
+            Some(left) => {
+                *left -= 1;
+                true
+            }
+            None => true
+        })
+    }
+
+    /// Up to `n` distinct values, no two of which share a group under `group`.
+    pub fn sample_n_distinct_grouped<G:Ord, F:Fn(&T)->G>(&self, n: usize, group: F) -> Vec<T> {
+        self.sample_n_distinct_grouped_with(n, group, &mut rand::thread_rng())
+    }
+
+    pub fn sample_n_distinct_grouped_with<G:Ord, F:Fn(&T)->G, R:Rng + ?Sized>(&self, n: usize, group: F, rng: &mut R) -> Vec<T> {
+        let mut used = BTreeSet::new();
+        self.sample_distinct(n, rng, |value| used.insert(group(value)))
+    }
+
+    /// The values of `must_include`, whether or not they are in this distribution, followed by
+    /// distinct values sampled to make up `n` in all.
+    pub fn sample_n_including(&self, n: usize, must_include: &[T]) -> Vec<T> {
+        self.sample_n_including_with(n, must_include, &mut rand::thread_rng())
+    }
+
+    pub fn sample_n_including_with<R:Rng + ?Sized>(&self, n: usize, must_include: &[T], rng: &mut R) -> Vec<T> {
+        let included: BTreeSet<&T> = must_include.iter().collect();
+        assert!(included.len() <= n);
+        let mut result: Vec<T> = Vec::with_capacity(n);
+        for value in must_include {
+            if !result.contains(value) {
+                result.push(value.clone());
+            }
+        }
+        result.extend(self.sample_distinct(n - result.len(), rng, |value| !included.contains(value)));
+        result
+    }
+
+    /// Picks `len` values with replacement, except that each value in `min_gap` is followed
+    /// by at least that many other values before it can appear again. Stops early if every
+    /// value is blocked.
+    pub fn sequence(&self, len: usize, min_gap: BTreeMap<T, usize>) -> Vec<T> {
+        self.sequence_with(len, min_gap, &mut rand::thread_rng())
+    }
+
+    pub fn sequence_with<R:Rng + ?Sized>(&self, len: usize, min_gap: BTreeMap<T, usize>, rng: &mut R) -> Vec<T> {
+        let mut last_seen: BTreeMap<&T, usize> = BTreeMap::new();
+        let mut result = Vec::with_capacity(len);
+        while result.len() < len {
+            let position = result.len();
+            let allowed = |value: &T| match (min_gap.get(value), last_seen.get(value)) {
+                (Some(gap), Some(seen)) => position - seen > *gap,
+                _ => true
+            };
+            let Some(picked) = pick_filtered(self, rng, allowed) else {
+                break;
+            };
+            if let Some((key, _)) = min_gap.get_key_value(picked) {
+                last_seen.insert(key, position);
+            }
+            result.push(picked.clone());
+        }
+        result
+    }
+
+    fn sample_distinct<R:Rng + ?Sized, F:FnMut(&T)->bool>(&self, n: usize, rng: &mut R, mut admit: F) -> Vec<T> {
+        let mut candidates: Vec<(&T, f64)> = self.originals.iter().map(|(value, weight)| (value, *weight)).collect();
+        let mut result = Vec::with_capacity(n.min(candidates.len()));
+        while result.len() < n {
+            let Some(chosen) = pick_weighted(candidates.iter().enumerate().map(|(i, (_, weight))| (i, *weight)), rng) else {
+                break;
+            };
+            let (value, _) = candidates.swap_remove(chosen);
+            if admit(value) {
+                result.push(self.notify_pick(value).clone());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::{Distribution, SplitMix64};
+
+    fn questions() -> Distribution<(char, u32)> {
+        let mut dist = Distribution::new();
+        for topic in 'a'..='c' {
+            for number in 0..5 {
+                dist.add(&(topic, number), if topic == 'a' {100.0} else {1.0});
+            }
+        }
+        dist
+    }
+
+    #[test]
+    fn test_sample_with_quotas() {
+        let dist = questions();
+        let mut limits = BTreeMap::new();
+        limits.insert('a', 3);
+        limits.insert('b', 1);
+        for _ in 0..20 {
+            let picked = dist.sample_with_quotas(7, |(topic, _)| *topic, limits.clone());
+            assert_eq!(picked.len(), 7);
+            assert_eq!(picked.iter().filter(|(topic, _)| *topic == 'a').count(), 3);
+            assert!(picked.iter().filter(|(topic, _)| *topic == 'b').count() <= 1);
+            let mut distinct = picked.clone();
+            distinct.sort();
+            distinct.dedup();
+            assert_eq!(distinct.len(), 7);
+        }
+        limits.insert('c', 0);
+        assert_eq!(dist.sample_with_quotas(10, |(topic, _)| *topic, limits.clone()).len(), 4);
+
+        let seeded = |seed| dist.sample_with_quotas_with(6, |(topic, _)| *topic, limits.clone(), &mut SplitMix64::new(seed));
+        assert_eq!(seeded(3), seeded(3));
+    }
+
+    #[test]
+    fn test_sample_n_distinct_grouped() {
+        let dist = questions();
+        for _ in 0..20 {
+            let picked = dist.sample_n_distinct_grouped(5, |(topic, _)| *topic);
+            assert_eq!(picked.len(), 3);
+            let mut topics: Vec<char> = picked.iter().map(|(topic, _)| *topic).collect();
+            topics.sort();
+            assert_eq!(topics, vec!['a', 'b', 'c']);
+        }
+        assert_eq!(dist.sample_n_distinct_grouped(2, |(_, number)| *number).len(), 2);
+
+        let seeded = |seed| dist.sample_n_distinct_grouped_with(4, |(_, number)| *number, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(5), seeded(5));
+    }
+
+    #[test]
+    fn test_sequence_min_gap() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 100.0);
+        dist.add(&'b', 1.0);
+        dist.add(&'c', 1.0);
+        let mut gaps = BTreeMap::new();
+        gaps.insert('a', 2);
+        let sequence = dist.sequence(300, gaps.clone());
+        let seeded = |seed| dist.sequence_with(50, gaps.clone(), &mut SplitMix64::new(seed));
+        assert_eq!(seeded(2), seeded(2));
+        assert_eq!(sequence.len(), 300);
+        let a_positions: Vec<usize> = sequence.iter().enumerate().filter(|(_, c)| **c == 'a').map(|(i, _)| i).collect();
+        assert!(a_positions.len() > 80);
+        assert!(a_positions.windows(2).all(|pair| pair[1] - pair[0] > 2));
+
+        gaps.insert('a', 5);
+        gaps.insert('b', 5);
+        gaps.insert('c', 5);
+        assert_eq!(dist.sequence(300, gaps).len(), 3);
+    }
+
+    #[test]
+    fn test_sample_n_including() {
+        let dist = questions();
+        let required = [('c', 4), ('z', 0), ('c', 4)];
+        let picked = dist.sample_n_including(6, &required);
+        assert_eq!(&picked[..2], &[('c', 4), ('z', 0)]);
+        assert_eq!(picked.len(), 6);
+        assert!(picked[2..].iter().all(|value| *value != ('c', 4)));
+        assert_eq!(dist.sample_n_including(100, &required).len(), 16);
+
+        let seeded = |seed| dist.sample_n_including_with(6, &required, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(4), seeded(4));
+    }
+}
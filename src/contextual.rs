@@ -0,0 +1,116 @@
+use rand::Rng;
+use crate::pick_weighted;
+
+type Condition<C> = Box<dyn Fn(&C) -> bool + Send + Sync>;
+
+/// Entries that may be restricted to contexts satisfying a condition, such as a minimum player
+/// level. Each pick renormalizes over the entries eligible in the supplied context.
+pub struct ContextualDistribution<T, C> {
+    entries: Vec<(T, f64, Option<Condition<C>>)>
+}
+
+impl <T:Clone + PartialEq, C> ContextualDistribution<T, C> {
+    pub fn new() -> Self {
+        ContextualDistribution {entries: Vec::new()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adding a value that is already present replaces its weight, and drops any condition.
+    pub fn add(&mut self, value: &T, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.insert(value, weight, None);
+    }
+
+    /// Like `add()`, but the value is only eligible in contexts satisfying `condition`. Adding a
+    /// value that is already present replaces both its weight and its condition.
+    pub fn add_when<F: Fn(&C) -> bool + Send + Sync + 'static>(&mut self, value: &T, weight: f64, condition: F) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.insert(value, weight, Some(Box::new(condition)));
+    }
+
+    pub fn eligible_weight(&self, context: &C) -> f64 {
+        self.eligible(context).map(|(_, weight)| weight).sum()
+    }
+
+    /// Returns `None` if no entry is eligible in `context`.
+    pub fn pick_in_context(&self, context: &C) -> Option<T> {
+        self.pick_in_context_with(context, &mut rand::thread_rng())
+    }
+
+    pub fn pick_in_context_with<R:Rng + ?Sized>(&self, context: &C, rng: &mut R) -> Option<T> {
+        pick_weighted(self.eligible(context), rng).cloned()
+    }
+
+    fn insert(&mut self, value: &T, weight: f64, condition: Option<Condition<C>>) {
+        match self.entries.iter_mut().find(|(present, _, _)| present == value) {
+            Some(entry) => *entry = (value.clone(), weight, condition),
+            None => self.entries.push((value.clone(), weight, condition))
+        }
+    }
+
+    fn eligible<'a>(&'a self, context: &'a C) -> impl Iterator<Item=(&'a T, f64)> {
+        self.entries.iter()
+            .filter(move |(_, _, condition)| condition.as_ref().is_none_or(|c| c(context)))
+            .map(|(value, weight, _)| (value, *weight))
+    }
+}
+
+impl <T:Clone + PartialEq, C> Default for ContextualDistribution<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ContextualDistribution, SplitMix64};
+
+    struct Player {
+        level: u32
+    }
+
+    #[test]
+    fn test_pick_in_context() {
+        let mut loot = ContextualDistribution::new();
+        loot.add(&"potion", 1.0);
+        loot.add_when(&"dragon scale", 1000.0, |p: &Player| p.level >= 50);
+
+        let novice = Player {level: 3};
+        assert_eq!(loot.eligible_weight(&novice), 1.0);
+        assert!((0..100).all(|_| loot.pick_in_context(&novice) == Some("potion")));
+
+        let veteran = Player {level: 60};
+        assert_eq!(loot.eligible_weight(&veteran), 1001.0);
+        assert!((0..100).any(|_| loot.pick_in_context(&veteran) == Some("dragon scale")));
+        let seeded = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..20).map(|_| loot.pick_in_context_with(&veteran, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(seeded(2), seeded(2));
+
+        let mut gated = ContextualDistribution::new();
+        gated.add_when(&1, 1.0, |p: &Player| p.level > 100);
+        assert_eq!(gated.pick_in_context(&veteran), None);
+    }
+
+    #[test]
+    fn test_add_replaces() {
+        let mut loot = ContextualDistribution::new();
+        loot.add(&"potion", 1.0);
+        loot.add(&"potion", 3.0);
+        assert_eq!(loot.len(), 1);
+        assert_eq!(loot.eligible_weight(&Player {level: 1}), 3.0);
+
+        loot.add_when(&"potion", 2.0, |p: &Player| p.level > 10);
+        assert_eq!(loot.len(), 1);
+        assert_eq!(loot.eligible_weight(&Player {level: 1}), 0.0);
+        assert_eq!(loot.eligible_weight(&Player {level: 20}), 2.0);
+    }
+}
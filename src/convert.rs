@@ -0,0 +1,169 @@
+// Conversions between distributions and plain weight maps.
+
+use std::{fmt::{self, Debug, Display}, collections::{BTreeMap, HashMap}, error::Error, hash::Hash, sync::Arc};
+use crate::Distribution;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DistributionError<T> {
+    Empty,
+    InvalidWeight(T, f64),
+    DuplicateValue(T)
+}
+
+impl <T:Debug> Display for DistributionError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistributionError::Empty => write!(f, "no values to build a distribution from"),
+            DistributionError::InvalidWeight(value, w) => write!(f, "weight {w} of {value:?} is not positive and finite"),
+            DistributionError::DuplicateValue(value) => write!(f, "{value:?} is listed more than once")
+        }
+    }
+}
+
+impl <T:Debug> Error for DistributionError<T> {}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Like `From<Vec<(T, f64)>>`, but reports empty input, bad weights, and repeated values
+    /// instead of panicking or letting the last weight win.
+    pub fn try_from_pairs<I:IntoIterator<Item=(T, f64)>>(pairs: I) -> Result<Self, DistributionError<T>> {
+        let mut originals = BTreeMap::new();
+        for (value, weight) in pairs {
+            if !(weight.is_finite() && weight > 0.0) {
+                return Err(DistributionError::InvalidWeight(value, weight));
+            }
+            if originals.contains_key(&value) {
+                return Err(DistributionError::DuplicateValue(value));
+            }
+            originals.insert(value, weight);
+        }
+        if originals.is_empty() {
+            return Err(DistributionError::Empty);
+        }
+        Ok(Distribution::from_originals(originals))
+    }
+
+    pub fn into_map(self) -> BTreeMap<T, f64> {
+        Arc::unwrap_or_clone(self.originals)
+    }
+
+    fn from_originals(originals: BTreeMap<T, f64>) -> Self {
+        assert!(originals.values().all(|weight| weight.is_finite() && *weight > 0.0));
+        let mut result = Distribution::new();
+        result.originals = Arc::new(originals);
+        result.rebuild();
+        result.trace_constructed("conversion");
+        result
+    }
+}
+
+/// Panics unless every weight is positive and finite.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> From<BTreeMap<T, f64>> for Distribution<T> {
+    fn from(weights: BTreeMap<T, f64>) -> Self {
+        Distribution::from_originals(weights)
+    }
+}
+
+/// Panics unless every weight is positive and finite.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Hash> From<HashMap<T, f64>> for Distribution<T> {
+    fn from(weights: HashMap<T, f64>) -> Self {
+        Distribution::from_originals(weights.into_iter().collect())
+    }
+}
+
+/// Panics unless every weight is positive and finite. A value listed more than once takes
+/// its last weight, as with `add()`.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> From<Vec<(T, f64)>> for Distribution<T> {
+    fn from(weights: Vec<(T, f64)>) -> Self {
+        Distribution::from_originals(weights.into_iter().collect())
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Hash> Distribution<T> {
+    pub fn to_hash_map(&self) -> HashMap<T, f64> {
+        self.originals.iter().map(|(value, weight)| (value.clone(), *weight)).collect()
+    }
+}
+
+// `TryFrom` takes references because the owned forms already have panicking `From` impls,
+// which come with an infallible blanket `TryFrom`.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> TryFrom<&[(T, f64)]> for Distribution<T> {
+    type Error = DistributionError<T>;
+
+    fn try_from(pairs: &[(T, f64)]) -> Result<Self, Self::Error> {
+        Distribution::try_from_pairs(pairs.iter().cloned())
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> TryFrom<&BTreeMap<T, f64>> for Distribution<T> {
+    type Error = DistributionError<T>;
+
+    fn try_from(weights: &BTreeMap<T, f64>) -> Result<Self, Self::Error> {
+        Distribution::try_from_pairs(weights.iter().map(|(value, weight)| (value.clone(), *weight)))
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Hash> TryFrom<&HashMap<T, f64>> for Distribution<T> {
+    type Error = DistributionError<T>;
+
+    fn try_from(weights: &HashMap<T, f64>) -> Result<Self, Self::Error> {
+        Distribution::try_from_pairs(weights.iter().map(|(value, weight)| (value.clone(), *weight)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use crate::{Distribution, DistributionError};
+
+    fn example() -> Distribution<char> {
+        let mut dist = Distribution::new();
+        dist.add(&'x', 2.0);
+        dist.add(&'y', 0.5);
+        dist
+    }
+
+    #[test]
+    fn test_into_maps() {
+        let dist = example();
+        let hashed = dist.to_hash_map();
+        assert_eq!(hashed.len(), 2);
+        assert_eq!(hashed[&'y'], 0.5);
+        let map = dist.into_map();
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![('x', 2.0), ('y', 0.5)]);
+    }
+
+    #[test]
+    fn test_from_maps_and_pairs() {
+        let from_vec: Distribution<char> = vec![('y', 3.0), ('x', 2.0), ('y', 0.5)].into();
+        assert_eq!(from_vec.originals, example().originals);
+        assert_eq!(from_vec.total_weight, 2.5);
+        assert_eq!(from_vec.value_at(2.2), Some(&'y'));
+
+        let btree: BTreeMap<char, f64> = example().into_map();
+        assert_eq!(Distribution::from(btree).originals, example().originals);
+        let hashed: HashMap<char, f64> = example().to_hash_map();
+        assert_eq!(Distribution::from(hashed).originals, example().originals);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_rejects_bad_weight() {
+        let _ = Distribution::from(vec![('x', f64::NAN)]);
+    }
+
+    #[test]
+    fn test_try_from() {
+        let pairs = [('y', 0.5), ('x', 2.0)];
+        assert_eq!(Distribution::try_from(&pairs[..]).unwrap().originals, example().originals);
+        assert_eq!(Distribution::try_from(&example().to_hash_map()).unwrap().total_weight, 2.5);
+
+        let empty: &[(char, f64)] = &[];
+        assert_eq!(Distribution::try_from(empty).unwrap_err(), DistributionError::Empty);
+        assert_eq!(Distribution::try_from(&[('x', 1.0), ('x', 2.0)][..]).unwrap_err(), DistributionError::DuplicateValue('x'));
+        let mut map = BTreeMap::new();
+        map.insert('z', -1.0);
+        assert_eq!(Distribution::try_from(&map).unwrap_err(), DistributionError::InvalidWeight('z', -1.0));
+        assert_eq!(Distribution::try_from_pairs(vec![('x', f64::INFINITY)]).unwrap_err().to_string(),
+                   "weight inf of 'x' is not positive and finite");
+    }
+}
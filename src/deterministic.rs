@@ -0,0 +1,185 @@
+// Everything in this module is specified down to the bit so that, given the same seed and the
+// same sequence of `add()` calls, picks are identical on every platform, toolchain, and version
+// of `rand`:
+//   - the generator is SplitMix64, implemented here rather than borrowed from `rand`;
+//   - weights are integers, so cumulative sums are exact;
+//   - draws are reduced to `[0, total)` with Lemire's unbiased multiply-and-reject method;
+//   - the cumulative table is laid out in insertion order, so equal weights never tie.
+// Changing any of these is a breaking change, guarded by the golden sequences in the tests.
+
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::RngCore;
+use crate::hashing::{mix64, GOLDEN_GAMMA};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 {state: seed}
+    }
+
+    /// A uniformly distributed integer in `[0, bound)`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        below(self, bound)
+    }
+}
+
+// Lemire's reduction, shared with `DeterministicDistribution::pick_with()` so that other
+// generators draw the same way SplitMix64 does.
+fn below<R:RngCore + ?Sized>(rng: &mut R, bound: u64) -> u64 {
+    assert!(bound > 0);
+    let mut product = rng.next_u64() as u128 * bound as u128;
+    if (product as u64) < bound {
+        let threshold = bound.wrapping_neg() % bound;
+        while (product as u64) < threshold {
+            product = rng.next_u64() as u128 * bound as u128;
+        }
+    }
+    (product >> 64) as u64
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix64(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// A distribution with integer weights whose picks are reproducible across platforms; see the
+/// module comment for the exact guarantees. Re-adding a value replaces its weight but keeps its
+/// original position.
+#[derive(Clone, Debug)]
+pub struct DeterministicDistribution<T> {
+    values: Vec<T>,
+    weights: Vec<u64>,
+    ends: Vec<u64>,
+    positions: BTreeMap<T, usize>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> DeterministicDistribution<T> {
+    pub fn new() -> Self {
+        DeterministicDistribution {values: Vec::new(), weights: Vec::new(), ends: Vec::new(), positions: BTreeMap::new()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn total_weight(&self) -> u64 {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    pub fn weight(&self, value: &T) -> Option<u64> {
+        self.positions.get(value).map(|i| self.weights[*i])
+    }
+
+    pub fn add(&mut self, value: &T, weight: u64) {
+        assert!(weight > 0);
+        let replaced = self.weight(value).unwrap_or(0);
+        let end = (self.total_weight() - replaced).checked_add(weight).expect("total weight overflows u64");
+        match self.positions.get(value) {
+            Some(i) => {
+                self.weights[*i] = weight;
+                self.rebuild_from(*i);
+            }
+            None => {
+                self.positions.insert(value.clone(), self.values.len());
+                self.values.push(value.clone());
+                self.weights.push(weight);
+                self.ends.push(end);
+            }
+        }
+    }
+
+    pub fn pick(&self, rng: &mut SplitMix64) -> &T {
+        self.pick_with(rng)
+    }
+
+    /// Picks as `pick()` does from any generator. Only a `SplitMix64` carries the guarantee
+    /// that picks are the same everywhere.
+    pub fn pick_with<R:RngCore + ?Sized>(&self, rng: &mut R) -> &T {
+        assert!(!self.is_empty());
+        let draw = below(rng, self.total_weight());
+        &self.values[self.ends.partition_point(|end| *end <= draw)]
+    }
+
+    // Only called once `add()` has checked that the new total fits.
+    fn rebuild_from(&mut self, start: usize) {
+        let mut end = if start == 0 {0} else {self.ends[start - 1]};
+        for i in start..self.values.len() {
+            end += self.weights[i];
+            self.ends[i] = end;
+        }
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for DeterministicDistribution<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use rand::RngCore;
+    use crate::{DeterministicDistribution, SplitMix64};
+
+    #[test]
+    fn test_splitmix_reference_sequence() {
+        let mut rng = SplitMix64::new(1234567);
+        let outputs: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+        assert_eq!(outputs, vec![6457827717110365317, 3203168211198807973, 9817491932198370423, 4593380528125082431, 16408922859458223821]);
+    }
+
+    #[test]
+    fn test_golden_pick_sequence() {
+        let mut dist = DeterministicDistribution::new();
+        dist.add(&'a', 1);
+        dist.add(&'b', 2);
+        dist.add(&'c', 3);
+        dist.add(&'d', 4);
+        dist.add(&'b', 5);
+        assert_eq!(dist.total_weight(), 13);
+        assert_eq!(dist.weight(&'b'), Some(5));
+
+        let mut rng = SplitMix64::new(2024);
+        let picks: String = (0..24).map(|_| *dist.pick(&mut rng)).collect();
+        assert_eq!(picks, "cbbbdcbcbbcddcbcdbdbccbb");
+    }
+
+    #[test]
+    fn test_overflowing_replacement_leaves_table_unchanged() {
+        let mut dist = DeterministicDistribution::new();
+        dist.add(&'a', u64::MAX - 1);
+        dist.add(&'b', 1);
+        dist.add(&'a', u64::MAX - 1);
+        let replaced = catch_unwind(AssertUnwindSafe(|| dist.add(&'b', 2)));
+        assert!(replaced.is_err());
+        assert_eq!(dist.weight(&'b'), Some(1));
+        assert_eq!(dist.total_weight(), u64::MAX);
+    }
+}
@@ -0,0 +1,74 @@
+// Differences between two weight tables, in ascending value order.
+
+use std::fmt::Debug;
+use crate::Distribution;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightChange<T> {
+    pub value: T,
+    pub old_weight: f64,
+    pub new_weight: f64
+}
+
+impl <T> WeightChange<T> {
+    pub fn delta(&self) -> f64 {
+        self.new_weight - self.old_weight
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionDiff<T> {
+    pub added: Vec<(T, f64)>,
+    pub removed: Vec<(T, f64)>,
+    pub changed: Vec<WeightChange<T>>
+}
+
+impl <T> DistributionDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// What it takes to turn this distribution into `other`. Weights are compared exactly.
+    pub fn diff(&self, other: &Distribution<T>) -> DistributionDiff<T> {
+        let mut result = DistributionDiff {added: Vec::new(), removed: Vec::new(), changed: Vec::new()};
+        for (value, old_weight) in self.originals.iter() {
+            match other.originals.get(value) {
+                None => result.removed.push((value.clone(), *old_weight)),
+                Some(new_weight) if new_weight != old_weight =>
+                    result.changed.push(WeightChange {value: value.clone(), old_weight: *old_weight, new_weight: *new_weight}),
+                Some(_) => {}
+            }
+        }
+        result.added = other.originals.iter()
+            .filter(|(value, _)| !self.originals.contains_key(value))
+            .map(|(value, weight)| (value.clone(), *weight))
+            .collect();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, WeightChange};
+
+    #[test]
+    fn test_diff() {
+        let mut before = Distribution::new();
+        before.add(&'a', 1.0);
+        before.add(&'b', 2.0);
+        before.add(&'c', 3.0);
+        assert!(before.diff(&before.clone()).is_empty());
+
+        let mut after = before.clone();
+        after.remove(&'a');
+        after.add(&'c', 4.5);
+        after.add(&'d', 0.5);
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, vec![('d', 0.5)]);
+        assert_eq!(diff.removed, vec![('a', 1.0)]);
+        assert_eq!(diff.changed, vec![WeightChange {value: 'c', old_weight: 3.0, new_weight: 4.5}]);
+        assert_eq!(diff.changed[0].delta(), 1.5);
+    }
+}
@@ -0,0 +1,115 @@
+use std::{fmt::{self, Debug}, collections::BTreeMap};
+use rand::Rng;
+use crate::{pick_weighted, Distribution};
+
+type WeightFn = Box<dyn Fn()->f64 + Send + Sync>;
+
+/// Values whose weights are closures evaluated at pick time, for weights that track live
+/// state such as queue depths or stock levels. Each pick evaluates every closure in one O(n)
+/// pass; when the weights hold still for a batch of picks, `evaluate()` captures them in a
+/// `Distribution` whose cumulative table makes each pick O(log n).
+pub struct DynamicDistribution<T> {
+    weights: BTreeMap<T, WeightFn>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> DynamicDistribution<T> {
+    pub fn new() -> Self {
+        DynamicDistribution {weights: BTreeMap::new()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Gives `value` the weight returned by `weight`, replacing any earlier closure. The
+    /// closure must return a finite, non-negative weight; zero means the value is skipped.
+    pub fn add<F:Fn()->f64 + Send + Sync + 'static>(&mut self, value: &T, weight: F) {
+        self.weights.insert(value.clone(), Box::new(weight));
+    }
+
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.weights.remove(value).is_some()
+    }
+
+    /// The current weight of `value`.
+    pub fn weight(&self, value: &T) -> Option<f64> {
+        self.weights.get(value).map(|weight| checked(weight()))
+    }
+
+    /// Returns `None` if every weight is currently zero.
+    pub fn random_pick(&self) -> Option<T> {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> Option<T> {
+        pick_weighted(self.weights.iter().map(|(value, weight)| (value, checked(weight()))), rng).cloned()
+    }
+
+    /// A distribution holding the current weights, leaving out values whose weight is zero.
+    pub fn evaluate(&self) -> Distribution<T> {
+        let mut result = Distribution::new();
+        for (value, weight) in self.weights.iter() {
+            let weight = checked(weight());
+            if weight > 0.0 {
+                result.add(value, weight);
+            }
+        }
+        result
+    }
+}
+
+fn checked(weight: f64) -> f64 {
+    assert!(weight.is_finite() && weight >= 0.0, "weight function returned {weight}");
+    weight
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for DynamicDistribution<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl <T:Debug> Debug for DynamicDistribution<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.weights.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    use crate::DynamicDistribution;
+
+    #[test]
+    fn test_live_weights() {
+        let stock = Arc::new(AtomicUsize::new(0));
+        let mut dist = DynamicDistribution::new();
+        let level = stock.clone();
+        dist.add(&"stocked", move || level.load(Ordering::Relaxed) as f64);
+        dist.add(&"fallback", || 1.0);
+
+        assert!((0..20).all(|_| dist.random_pick() == Some("fallback")));
+        stock.store(1000, Ordering::Relaxed);
+        assert_eq!(dist.weight(&"stocked"), Some(1000.0));
+        assert!((0..100).filter(|_| dist.random_pick() == Some("stocked")).count() > 80);
+
+        assert!(dist.remove(&"fallback"));
+        stock.store(0, Ordering::Relaxed);
+        assert_eq!(dist.random_pick(), None);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mut dist = DynamicDistribution::new();
+        dist.add(&'a', || 2.0);
+        dist.add(&'b', || 0.0);
+        dist.add(&'c', || 3.0);
+        let snapshot = dist.evaluate();
+        assert_eq!(snapshot.keys().copied().collect::<String>(), "ac");
+        assert_eq!(snapshot.total_weight, 5.0);
+    }
+}
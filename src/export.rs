@@ -0,0 +1,69 @@
+use std::fmt::Debug;
+use crate::Distribution;
+
+/// How weights are written by `to_bytes_with()` and `write_csv()`. The default writes raw
+/// weights at full precision.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ExportOptions {
+    /// Write probabilities summing to one instead of raw weights.
+    pub normalize: bool,
+    /// Round each written weight to this many decimal places. Values whose weight rounds to
+    /// zero are left out.
+    pub decimals: Option<u32>
+}
+
+impl ExportOptions {
+    pub fn normalized() -> Self {
+        ExportOptions {normalize: true, decimals: None}
+    }
+
+    pub fn with_decimals(self, decimals: u32) -> Self {
+        ExportOptions {decimals: Some(decimals), ..self}
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    pub(crate) fn exported_weights(&self, options: ExportOptions) -> Vec<(&T, f64)> {
+        let scale = if options.normalize {self.total_weight} else {1.0};
+        self.originals.iter()
+            .map(|(value, weight)| (value, round_to(*weight / scale, options.decimals)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect()
+    }
+}
+
+// A weight too large to scale by 10^decimals has no digits that far past the point, so it is
+// returned unrounded.
+fn round_to(weight: f64, decimals: Option<u32>) -> f64 {
+    match decimals {
+        Some(decimals) => {
+            let factor = 10f64.powi(decimals.min(i32::MAX as u32) as i32);
+            let scaled = weight * factor;
+            if scaled.is_finite() {scaled.round() / factor} else {weight}
+        }
+        None => weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, ExportOptions, export::round_to};
+
+    #[test]
+    fn test_exported_weights() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 2.0);
+        dist.add(&'c', 0.001);
+        assert_eq!(dist.exported_weights(ExportOptions::default()), vec![(&'a', 1.0), (&'b', 2.0), (&'c', 0.001)]);
+        assert_eq!(dist.exported_weights(ExportOptions::normalized().with_decimals(2)), vec![(&'a', 0.33), (&'b', 0.67)]);
+        assert_eq!(dist.exported_weights(ExportOptions::default().with_decimals(400)), dist.exported_weights(ExportOptions::default()));
+    }
+
+    #[test]
+    fn test_round_to_extremes() {
+        assert_eq!(round_to(0.125, Some(u32::MAX)), 0.125);
+        assert_eq!(round_to(1e300, Some(20)), 1e300);
+        assert_eq!(round_to(1.5e-20, Some(20)), 2e-20);
+    }
+}
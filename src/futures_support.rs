@@ -0,0 +1,45 @@
+use std::fmt::Debug;
+use futures::{stream, Stream};
+use rand::Rng;
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// An endless stream of picks. The stream never waits, so pace it with the combinators
+    /// of whichever runtime drives it.
+    pub fn sample_stream(&self) -> impl Stream<Item=T> + '_ {
+        assert!(!self.is_empty());
+        stream::repeat_with(|| self.random_pick())
+    }
+
+    /// Like `sample_stream()`, drawing from `rng`, so seeded streams are reproducible.
+    pub fn sample_stream_with<'a, R:Rng + 'a>(&'a self, mut rng: R) -> impl Stream<Item=T> + 'a {
+        assert!(!self.is_empty());
+        stream::repeat_with(move || self.notify_pick(self.pick_ref(&mut rng)).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, StreamExt};
+    use crate::{Distribution, SplitMix64};
+
+    fn example() -> Distribution<char> {
+        vec![('a', 1.0), ('b', 3.0)].into()
+    }
+
+    #[test]
+    fn test_sample_stream() {
+        let dist = example();
+        let picks: Vec<char> = block_on(dist.sample_stream().take(200).collect());
+        assert_eq!(picks.len(), 200);
+        assert!(picks.iter().filter(|c| **c == 'b').count() > 100);
+    }
+
+    #[test]
+    fn test_sample_stream_with() {
+        let dist = example();
+        let first: Vec<char> = block_on(dist.sample_stream_with(SplitMix64::new(9)).take(20).collect());
+        let second: Vec<char> = block_on(dist.sample_stream_with(SplitMix64::new(9)).take(20).collect());
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,72 @@
+// The Gumbel-max trick: adding independent Gumbel noise to each log-weight and taking the
+// largest results is the same as picking by weight, and the k largest are a weighted sample
+// of k distinct values.
+
+use std::fmt::Debug;
+use rand::Rng;
+use crate::Distribution;
+
+fn gumbel<R:Rng + ?Sized>(rng: &mut R) -> f64 {
+    -(-rng.gen_range(f64::MIN_POSITIVE..1.0).ln()).ln()
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    pub fn pick_gumbel(&self) -> T {
+        self.pick_gumbel_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_gumbel_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.top_k_gumbel_with(1, rng).pop().expect("empty distribution")
+    }
+
+    /// Up to `k` distinct values sampled without replacement, most likely first, in one pass.
+    pub fn top_k_gumbel(&self, k: usize) -> Vec<T> {
+        self.top_k_gumbel_with(k, &mut rand::thread_rng())
+    }
+
+    pub fn top_k_gumbel_with<R:Rng + ?Sized>(&self, k: usize, rng: &mut R) -> Vec<T> {
+        let mut keyed: Vec<(f64, &T)> = self.originals.iter().map(|(value, weight)| (weight.ln() + gumbel(rng), value)).collect();
+        let k = k.min(keyed.len());
+        if k > 0 && k < keyed.len() {
+            keyed.select_nth_unstable_by(k - 1, |a, b| b.0.total_cmp(&a.0));
+        }
+        keyed.truncate(k);
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+        keyed.into_iter().map(|(_, value)| self.notify_pick(value).clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testing::assert_counts_match_weights, Distribution, SplitMix64};
+
+    #[test]
+    fn test_pick_gumbel_matches_weights() {
+        let dist: Distribution<char> = vec![('a', 1.0), ('b', 2.0), ('c', 7.0)].into();
+        let mut counts = std::collections::BTreeMap::new();
+        for _ in 0..5000 {
+            *counts.entry(dist.pick_gumbel()).or_insert(0) += 1;
+        }
+        assert_counts_match_weights(&dist, &counts, 1e-6);
+    }
+
+    #[test]
+    fn test_top_k_gumbel() {
+        let dist: Distribution<char> = vec![('a', 1.0), ('b', 2.0), ('c', 1e9)].into();
+        let top = dist.top_k_gumbel(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0], 'c');
+        assert_ne!(top[1], 'c');
+        assert_eq!(dist.top_k_gumbel(10).len(), 3);
+        assert!(dist.top_k_gumbel(0).is_empty());
+    }
+
+    #[test]
+    fn test_seeded_gumbel() {
+        let dist: Distribution<u32> = (0..20).map(|i| (i, 1.0 + i as f64)).collect::<Vec<_>>().into();
+        let first = dist.top_k_gumbel_with(5, &mut SplitMix64::new(31));
+        assert_eq!(first, dist.top_k_gumbel_with(5, &mut SplitMix64::new(31)));
+        assert_eq!(first.iter().collect::<std::collections::BTreeSet<_>>().len(), 5);
+        assert_eq!(dist.pick_gumbel_with(&mut SplitMix64::new(2)), dist.pick_gumbel_with(&mut SplitMix64::new(2)));
+    }
+}
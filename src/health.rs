@@ -0,0 +1,91 @@
+use std::{fmt::Debug, collections::BTreeMap, time::{Duration, Instant}};
+use rand::Rng;
+use crate::{views::pick_filtered, Distribution};
+
+/// Skips values marked down until their time-to-live expires, after which they are picked again
+/// with their original weight. The underlying distribution is never rebuilt.
+#[derive(Clone, Debug)]
+pub struct HealthTracker<T> {
+    dist: Distribution<T>,
+    down_until: BTreeMap<T, Instant>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> HealthTracker<T> {
+    pub fn new(dist: Distribution<T>) -> Self {
+        HealthTracker {dist, down_until: BTreeMap::new()}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    pub fn mark_down(&mut self, value: &T, ttl: Duration) {
+        self.mark_down_at(value, ttl, Instant::now());
+    }
+
+    pub fn mark_down_at(&mut self, value: &T, ttl: Duration, now: Instant) {
+        self.down_until.retain(|_, until| *until > now);
+        self.down_until.insert(value.clone(), now + ttl);
+    }
+
+    pub fn mark_up(&mut self, value: &T) {
+        self.down_until.remove(value);
+    }
+
+    pub fn is_healthy(&self, value: &T) -> bool {
+        self.is_healthy_at(value, Instant::now())
+    }
+
+    pub fn is_healthy_at(&self, value: &T, now: Instant) -> bool {
+        self.down_until.get(value).is_none_or(|until| *until <= now)
+    }
+
+    /// Returns `None` if every value is currently down.
+    pub fn random_pick(&self) -> Option<T> {
+        self.random_pick_at(Instant::now())
+    }
+
+    pub fn random_pick_at(&self, now: Instant) -> Option<T> {
+        self.random_pick_at_with(now, &mut rand::thread_rng())
+    }
+
+    pub fn random_pick_at_with<R:Rng + ?Sized>(&self, now: Instant, rng: &mut R) -> Option<T> {
+        pick_filtered(&self.dist, rng, |value| self.is_healthy_at(value, now)).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::{Distribution, HealthTracker, SplitMix64};
+
+    #[test]
+    fn test_ttl_exclusion() {
+        let mut dist = Distribution::new();
+        dist.add(&"primary", 100.0);
+        dist.add(&"backup", 1.0);
+        let mut tracker = HealthTracker::new(dist);
+        let start = Instant::now();
+        tracker.mark_down_at(&"primary", Duration::from_secs(30), start);
+
+        let during = start + Duration::from_secs(10);
+        assert!(!tracker.is_healthy_at(&"primary", during));
+        assert!((0..100).all(|_| tracker.random_pick_at(during) == Some("backup")));
+
+        tracker.mark_down_at(&"backup", Duration::from_secs(5), start);
+        assert_eq!(tracker.random_pick_at(during - Duration::from_secs(8)), None);
+
+        let after = start + Duration::from_secs(31);
+        assert!(tracker.is_healthy_at(&"primary", after));
+        assert!((0..100).any(|_| tracker.random_pick_at(after) == Some("primary")));
+
+        tracker.mark_up(&"primary");
+        assert!(tracker.is_healthy_at(&"primary", during));
+
+        let seeded = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..20).map(|_| tracker.random_pick_at_with(after, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(seeded(8), seeded(8));
+    }
+}
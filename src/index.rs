@@ -0,0 +1,69 @@
+use rand::Rng;
+use crate::{pick_from_ends, weight_from_ends};
+
+/// Picks indices into a caller's own slice of values, so the values need no trait bounds.
+/// Indices with zero weight are never picked.
+#[derive(Clone, Debug)]
+pub struct IndexDistribution {
+    // ends[i] is the total weight of indices 0..=i.
+    ends: Vec<f64>
+}
+
+impl IndexDistribution {
+    pub fn new(weights: &[f64]) -> Self {
+        let mut total = 0.0;
+        let ends: Vec<f64> = weights.iter().map(|weight| {
+            assert!(weight.is_finite() && *weight >= 0.0);
+            total += weight;
+            total
+        }).collect();
+        assert!(total > 0.0, "no index has positive weight");
+        IndexDistribution {ends}
+    }
+
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        *self.ends.last().unwrap()
+    }
+
+    pub fn weight(&self, index: usize) -> Option<f64> {
+        weight_from_ends(&self.ends, index)
+    }
+
+    pub fn random_pick(&self) -> usize {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        pick_from_ends(&self.ends, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IndexDistribution, SplitMix64};
+
+    #[test]
+    fn test_index_distribution() {
+        let dist = IndexDistribution::new(&[0.0, 1.0, 0.0, 3.0]);
+        assert_eq!(dist.len(), 4);
+        assert_eq!(dist.total_weight(), 4.0);
+        assert_eq!(dist.weight(3), Some(3.0));
+        assert_eq!(dist.weight(4), None);
+
+        let mut rng = SplitMix64::new(3);
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            counts[dist.pick_with(&mut rng)] += 1;
+        }
+        assert_eq!(counts[0] + counts[2], 0);
+        assert!((2850..3150).contains(&counts[3]));
+    }
+}
@@ -0,0 +1,102 @@
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::Rng;
+use crate::Distribution;
+
+/// Wraps a distribution and counts how often each value is picked.
+#[derive(Clone, Debug)]
+pub struct Instrumented<T> {
+    dist: Distribution<T>,
+    counts: BTreeMap<T, u64>,
+    total_picks: u64
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportLine<T> {
+    pub value: T,
+    pub picks: u64,
+    pub expected: f64,
+    pub empirical: f64
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickReport<T> {
+    pub total_picks: u64,
+    pub lines: Vec<ReportLine<T>>,
+    /// The largest absolute difference between an expected and an empirical probability.
+    pub max_deviation: f64
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Instrumented<T> {
+    pub fn new(dist: Distribution<T>) -> Self {
+        Instrumented {dist, counts: BTreeMap::new(), total_picks: 0}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    pub fn into_inner(self) -> Distribution<T> {
+        self.dist
+    }
+
+    pub fn total_picks(&self) -> u64 {
+        self.total_picks
+    }
+
+    pub fn picks_of(&self, value: &T) -> u64 {
+        self.counts.get(value).copied().unwrap_or(0)
+    }
+
+    pub fn pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        let picked = self.dist.notify_pick(self.dist.pick_ref(rng)).clone();
+        *self.counts.entry(picked.clone()).or_insert(0) += 1;
+        self.total_picks += 1;
+        picked
+    }
+
+    pub fn reset_counts(&mut self) {
+        self.counts.clear();
+        self.total_picks = 0;
+    }
+
+    pub fn report(&self) -> PickReport<T> {
+        let lines: Vec<ReportLine<T>> = self.dist.probabilities()
+            .map(|(value, expected)| {
+                let picks = self.picks_of(value);
+                let empirical = if self.total_picks == 0 {0.0} else {picks as f64 / self.total_picks as f64};
+                ReportLine {value: value.clone(), picks, expected, empirical}
+            })
+            .collect();
+        let max_deviation = lines.iter().map(|line| (line.expected - line.empirical).abs()).fold(0.0, f64::max);
+        PickReport {total_picks: self.total_picks, lines, max_deviation}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, Instrumented};
+
+    #[test]
+    fn test_report() {
+        let mut dist = Distribution::new();
+        dist.add(&"a", 1.0);
+        dist.add(&"b", 3.0);
+        let mut instrumented = Instrumented::new(dist);
+        for _ in 0..2000 {
+            instrumented.pick();
+        }
+        let report = instrumented.report();
+        assert_eq!(report.total_picks, 2000);
+        assert_eq!(report.lines.len(), 2);
+        assert_eq!(report.lines[0].expected, 0.25);
+        assert_eq!(report.lines.iter().map(|l| l.picks).sum::<u64>(), 2000);
+        assert!(report.max_deviation < 0.05);
+
+        instrumented.reset_counts();
+        assert_eq!(instrumented.report().max_deviation, 0.75);
+    }
+}
@@ -0,0 +1,55 @@
+use rand::{rngs::ThreadRng, Rng};
+use crate::pick_weighted;
+
+/// Merges several iterators, advancing a randomly chosen one at each step in proportion to
+/// its weight. An exhausted source drops out and the rest keep their relative weights.
+pub fn interleave_weighted<I:Iterator>(sources: Vec<(I, f64)>) -> InterleaveWeighted<I> {
+    interleave_weighted_with(sources, rand::thread_rng())
+}
+
+/// As `interleave_weighted()`, choosing each source with `rng`, so a seeded generator merges
+/// the same sources in the same order every time.
+pub fn interleave_weighted_with<I:Iterator, R:Rng>(sources: Vec<(I, f64)>, rng: R) -> InterleaveWeighted<I, R> {
+    assert!(sources.iter().all(|(_, weight)| weight.is_finite() && *weight > 0.0));
+    InterleaveWeighted {sources, rng}
+}
+
+pub struct InterleaveWeighted<I, R = ThreadRng> {
+    sources: Vec<(I, f64)>,
+    rng: R
+}
+
+impl <I:Iterator, R:Rng> Iterator for InterleaveWeighted<I, R> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let chosen = pick_weighted(self.sources.iter().enumerate().map(|(i, (_, weight))| (i, *weight)), &mut self.rng)?;
+            match self.sources[chosen].0.next() {
+                Some(item) => return Some(item),
+                None => {
+                    self.sources.swap_remove(chosen);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{interleave_weighted, interleave_weighted_with, SplitMix64};
+
+    #[test]
+    fn test_interleave_weighted() {
+        let merged: Vec<u32> = interleave_weighted(vec![(0..3000, 3.0), (10_000..10_100, 1.0)]).collect();
+        assert_eq!(merged.len(), 3100);
+        let early_second = merged[..200].iter().filter(|n| **n >= 10_000).count();
+        assert!((20..80).contains(&early_second));
+        let first: Vec<u32> = merged.iter().copied().filter(|n| *n < 10_000).collect();
+        assert_eq!(first, (0..3000).collect::<Vec<_>>());
+
+        let seeded = |seed| interleave_weighted_with(vec![(0..50, 1.0), (100..150, 2.0)], SplitMix64::new(seed)).collect::<Vec<_>>();
+        assert_eq!(seeded(7), seeded(7));
+        assert_ne!(seeded(7), seeded(8));
+    }
+}
@@ -0,0 +1,77 @@
+use rand::Rng;
+use crate::Distribution;
+
+/// Picks a `[start, end)` interval by weight, then a uniformly random point within it.
+#[derive(Clone, Debug)]
+pub struct IntervalDistribution {
+    intervals: Vec<(f64, f64)>,
+    weights: Distribution<usize>
+}
+
+impl IntervalDistribution {
+    pub fn new() -> Self {
+        IntervalDistribution {intervals: Vec::new(), weights: Distribution::new()}
+    }
+
+    /// Panics unless `start < end` and the width `end - start` is finite.
+    pub fn add(&mut self, start: f64, end: f64, weight: f64) {
+        assert!(start < end && (end - start).is_finite(), "interval [{start}, {end}) must have a finite positive width");
+        self.weights.add(&self.intervals.len(), weight);
+        self.intervals.push((start, end));
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn sample(&self) -> f64 {
+        self.sample_with(&mut rand::thread_rng())
+    }
+
+    pub fn sample_with<R:Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let (start, end) = self.intervals[*self.weights.pick_ref(rng)];
+        rng.gen_range(start..end)
+    }
+}
+
+impl Default for IntervalDistribution {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<((f64, f64), f64)> for IntervalDistribution {
+    fn from_iter<I:IntoIterator<Item=((f64, f64), f64)>>(iter: I) -> Self {
+        let mut result = IntervalDistribution::new();
+        for ((start, end), weight) in iter {
+            result.add(start, end, weight);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IntervalDistribution, SplitMix64};
+
+    #[test]
+    fn test_interval_distribution() {
+        let hours: IntervalDistribution = [((9.0, 12.0), 1.0), ((13.0, 17.0), 3.0)].into_iter().collect();
+        assert_eq!(hours.len(), 2);
+        let mut rng = SplitMix64::new(21);
+        let samples: Vec<f64> = (0..4000).map(|_| hours.sample_with(&mut rng)).collect();
+        assert!(samples.iter().all(|h| (9.0..12.0).contains(h) || (13.0..17.0).contains(h)));
+        let afternoon = samples.iter().filter(|h| **h >= 13.0).count();
+        assert!((2850..3150).contains(&afternoon));
+    }
+
+    #[test]
+    #[should_panic(expected = "finite positive width")]
+    fn test_width_overflow() {
+        IntervalDistribution::new().add(-f64::MAX, f64::MAX, 1.0);
+    }
+}
@@ -0,0 +1,182 @@
+// Joint distributions over pairs of values, and the grouping that collapses them back down.
+
+use std::{fmt::Debug, collections::BTreeMap};
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// The joint distribution of independent picks from this and `other`. Each pair's weight is
+    /// the product of the two probabilities, so the weights sum to one however large the inputs
+    /// are. Pairs whose weight underflows to zero are left out, as they could never be picked.
+    pub fn product<U:Clone + PartialEq + Eq + PartialOrd + Ord + Debug>(&self, other: &Distribution<U>) -> Distribution<(T, U)> {
+        let mut result = Distribution::new();
+        for (left, left_weight) in self.originals.iter() {
+            for (right, right_weight) in other.originals.iter() {
+                let weight = (left_weight / self.total_weight) * (right_weight / other.total_weight);
+                if weight > 0.0 {
+                    result.add(&(left.clone(), right.clone()), weight);
+                }
+            }
+        }
+        result
+    }
+
+    /// A distribution over groups, where each group's weight is the sum of the weights of the
+    /// values that `group` maps to it. Panics if a group's sum overflows `f64`.
+    pub fn group_by<G:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, F:Fn(&T)->G>(&self, group: F) -> Distribution<G> {
+        let mut sums = BTreeMap::new();
+        for (value, weight) in self.originals.iter() {
+            *sums.entry(group(value)).or_insert(0.0) += weight;
+        }
+        let mut result = Distribution::new();
+        for (group, weight) in sums.iter() {
+            assert!(weight.is_finite(), "weight of group {group:?} overflows f64");
+            result.add(group, *weight);
+        }
+        result
+    }
+}
+
+impl <A:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, B:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<(A, B)> {
+    pub fn marginal_left(&self) -> Distribution<A> {
+        self.group_by(|(a, _)| a.clone())
+    }
+
+    pub fn marginal_right(&self) -> Distribution<B> {
+        self.group_by(|(_, b)| b.clone())
+    }
+
+    /// The distribution of the second component among pairs whose first is `a`, with
+    /// weights normalized to sum to one. Empty if no pair starts with `a`.
+    pub fn given_left(&self, a: &A) -> Distribution<B> {
+        conditional(self.originals.iter().filter(|((left, _), _)| left == a).map(|((_, right), weight)| (right, *weight)))
+    }
+
+    /// The distribution of the first component among pairs whose second is `b`, normalized likewise.
+    pub fn given_right(&self, b: &B) -> Distribution<A> {
+        conditional(self.originals.iter().filter(|((_, right), _)| right == b).map(|((left, _), weight)| (left, *weight)))
+    }
+}
+
+// Scales by the largest weight before summing, so the total cannot overflow; results that
+// underflow to zero are left out, as in `product()`.
+fn conditional<'a, T:'a + Clone + PartialEq + Eq + PartialOrd + Ord + Debug, I:Iterator<Item=(&'a T, f64)>>(matches: I) -> Distribution<T> {
+    let matches: Vec<_> = matches.collect();
+    let largest = matches.iter().map(|(_, weight)| *weight).fold(0.0, f64::max);
+    let total: f64 = matches.iter().map(|(_, weight)| weight / largest).sum();
+    let mut result = Distribution::new();
+    for (value, weight) in matches {
+        let probability = weight / largest / total;
+        if probability.is_finite() && probability > 0.0 {
+            result.add(value, probability);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Distribution;
+
+    #[test]
+    fn test_product() {
+        let mut coin = Distribution::new();
+        coin.add(&'h', 1.0);
+        coin.add(&'t', 3.0);
+        let mut die = Distribution::new();
+        for face in 1..=3 {
+            die.add(&face, face as f64);
+        }
+        let joint = coin.product(&die);
+        assert_eq!(joint.originals.len(), 6);
+        assert!((joint.total_weight - 1.0).abs() < 1e-12);
+        assert!((joint.originals[&('t', 2)] - 0.25).abs() < 1e-12);
+        let (side, face) = joint.random_pick();
+        assert!("ht".contains(side) && (1..=3).contains(&face));
+    }
+
+    #[test]
+    fn test_product_skips_underflow() {
+        let tiny: Distribution<char> = vec![('a', 1e-200), ('b', 1.0)].into();
+        let joint = tiny.product(&tiny);
+        assert_eq!(joint.originals.len(), 3);
+        assert!(!joint.originals.contains_key(&('a', 'a')));
+
+        let dice: Distribution<i64> = vec![(0, 1e-200), (1, 1.0)].into();
+        assert_eq!(dice.convolve(&dice).originals.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_product_of_huge_weights() {
+        let huge: Distribution<char> = vec![('a', 1e200), ('b', 3e200)].into();
+        let joint = huge.product(&huge);
+        assert!((joint.total_weight - 1.0).abs() < 1e-12);
+        assert!((joint.originals[&('b', 'b')] - 0.5625).abs() < 1e-12);
+
+        let dice: Distribution<i64> = vec![(0, 1e200), (1, 1e200)].into();
+        let sums = dice.convolve(&dice);
+        assert!((sums.originals[&1] - 0.5).abs() < 1e-12);
+        assert!((sums.total_weight - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_marginals() {
+        let mut joint = Distribution::new();
+        joint.add(&('a', 1), 1.0);
+        joint.add(&('a', 2), 2.0);
+        joint.add(&('b', 1), 4.0);
+        let left = joint.marginal_left();
+        assert_eq!(left.originals.len(), 2);
+        assert_eq!(left.originals[&'a'], 3.0);
+        assert_eq!(left.originals[&'b'], 4.0);
+        let right = joint.marginal_right();
+        assert_eq!(right.originals[&1], 5.0);
+        assert_eq!(right.originals[&2], 2.0);
+        assert_eq!(right.total_weight, joint.total_weight);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let dist: Distribution<u32> = (1..=6).map(|face| (face, face as f64)).collect::<Vec<_>>().into();
+        let parity = dist.group_by(|face| face % 2 == 0);
+        assert_eq!(parity.originals.len(), 2);
+        assert_eq!(parity.originals[&false], 9.0);
+        assert_eq!(parity.originals[&true], 12.0);
+        assert_eq!(parity.total_weight, dist.total_weight);
+        assert!(Distribution::<u32>::new().group_by(|face| *face).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "weight of group true overflows f64")]
+    fn test_group_by_overflow() {
+        let dist: Distribution<u32> = vec![(1, 1e308), (3, 1e308)].into();
+        dist.group_by(|face| face % 2 == 1);
+    }
+
+    #[test]
+    fn test_given() {
+        let mut joint = Distribution::new();
+        joint.add(&('a', 1), 1.0);
+        joint.add(&('a', 2), 3.0);
+        joint.add(&('b', 1), 4.0);
+        let given_a = joint.given_left(&'a');
+        assert_eq!(given_a.originals[&1], 0.25);
+        assert_eq!(given_a.originals[&2], 0.75);
+        assert_eq!(given_a.total_weight, 1.0);
+        let given_1 = joint.given_right(&1);
+        assert_eq!(given_1.originals[&'a'], 0.2);
+        assert_eq!(given_1.originals[&'b'], 0.8);
+        assert!(joint.given_left(&'z').is_empty());
+    }
+
+    #[test]
+    fn test_given_extreme_weights() {
+        let mut joint = Distribution::new();
+        joint.add(&('a', 1), f64::MAX);
+        joint.add(&('a', 2), f64::MAX);
+        joint.add(&('a', 3), 1e-300);
+        let given_a = joint.given_left(&'a');
+        assert_eq!(given_a.originals.len(), 2);
+        assert_eq!(given_a.originals[&1], 0.5);
+        assert_eq!(given_a.total_weight, 1.0);
+    }
+}
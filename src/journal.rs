@@ -0,0 +1,134 @@
+// Every mutation is stored as the value's weight before and after, with `None` meaning
+// absent, so undo and redo are the same operation applied in opposite directions.
+
+use std::fmt::Debug;
+use crate::Distribution;
+
+#[derive(Clone, Debug)]
+struct Change<T> {
+    value: T,
+    before: Option<f64>,
+    after: Option<f64>
+}
+
+/// A distribution that remembers its mutations so they can be undone and redone.
+#[derive(Clone, Debug)]
+pub struct Journaled<T> {
+    dist: Distribution<T>,
+    undo: Vec<Change<T>>,
+    redo: Vec<Change<T>>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Journaled<T> {
+    pub fn new(dist: Distribution<T>) -> Self {
+        Journaled {dist, undo: Vec::new(), redo: Vec::new()}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    pub fn into_distribution(self) -> Distribution<T> {
+        self.dist
+    }
+
+    pub fn add(&mut self, value: &T, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.record(value, Some(weight));
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<f64> {
+        let weight = self.dist.originals.get(value).copied()?;
+        self.record(value, None);
+        Some(weight)
+    }
+
+    /// Changes the weight of a value already present, returning its old weight.
+    pub fn set_weight(&mut self, value: &T, weight: f64) -> Option<f64> {
+        assert!(weight.is_finite() && weight > 0.0);
+        let old = self.dist.originals.get(value).copied()?;
+        self.record(value, Some(weight));
+        Some(old)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Reverts the most recent mutation; returns false if there is none.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(change) => {
+                self.apply(&change.value, change.before);
+                self.redo.push(change);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Reapplies the most recently undone mutation; returns false if there is none.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(change) => {
+                self.apply(&change.value, change.after);
+                self.undo.push(change);
+                true
+            }
+            None => false
+        }
+    }
+
+    fn record(&mut self, value: &T, after: Option<f64>) {
+        let before = self.dist.originals.get(value).copied();
+        self.apply(value, after);
+        self.undo.push(Change {value: value.clone(), before, after});
+        self.redo.clear();
+    }
+
+    fn apply(&mut self, value: &T, weight: Option<f64>) {
+        match weight {
+            Some(weight) => self.dist.add(value, weight),
+            None => {
+                self.dist.remove(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, Journaled};
+
+    #[test]
+    fn test_undo_redo() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        let mut journal = Journaled::new(dist);
+        journal.add(&'b', 2.0);
+        assert_eq!(journal.set_weight(&'a', 5.0), Some(1.0));
+        assert_eq!(journal.remove(&'b'), Some(2.0));
+        assert_eq!(journal.set_weight(&'z', 1.0), None);
+        assert_eq!(journal.distribution().total_weight, 5.0);
+
+        assert!(journal.undo());
+        assert_eq!(journal.distribution().originals[&'b'], 2.0);
+        assert!(journal.undo());
+        assert_eq!(journal.distribution().originals[&'a'], 1.0);
+        assert!(journal.redo());
+        assert_eq!(journal.distribution().originals[&'a'], 5.0);
+        assert!(journal.undo());
+        assert!(journal.undo());
+        assert!(!journal.undo());
+        assert_eq!(journal.distribution().originals.len(), 1);
+        assert_eq!(journal.distribution().total_weight, 1.0);
+
+        journal.add(&'c', 3.0);
+        assert!(!journal.can_redo());
+        assert!(!journal.redo());
+    }
+}
@@ -1,37 +1,440 @@
 // Based on: https://stackoverflow.com/questions/6737283/weighted-randomness-in-java
 
-use std::{fmt::Debug,collections::BTreeMap};
-use ordered_float::OrderedFloat;
+use std::{fmt::Debug,collections::BTreeMap,hash::{Hash, Hasher},ops::Index,sync::Arc};
 use rand::Rng;
+use hashing::{splitmix64, unit_interval, StableHasher};
+use observer::ObserverSlot;
+
+// Lets `static_distribution!` name this crate by its published path from inside its own tests.
+#[cfg(feature = "macros")]
+extern crate self as distribution_select;
+
+mod annealing;
+mod approx;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_support;
+mod arithmetic;
+mod batch;
+mod bayes;
+mod bins;
+mod bytes;
+mod consistent;
+mod constrained;
+mod contextual;
+mod convert;
+mod deterministic;
+mod diff;
+mod dynamic;
+mod export;
+#[cfg(feature = "futures")]
+mod futures_support;
+mod gumbel;
+mod hashing;
+mod health;
+mod index;
+mod instrumented;
+mod interleave;
+mod interval;
+mod joint;
+mod journal;
+mod loot;
+mod markov;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "ndarray")]
+mod ndarray_support;
+mod observer;
+mod paced;
+mod piecewise;
+mod pity;
+#[cfg(feature = "plot")]
+mod plot;
+#[cfg(feature = "polars")]
+mod polars_support;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_support;
+mod ranges;
+mod ranking;
+mod recency;
+mod replay;
+mod reservoir;
+mod round_robin;
+pub mod selection;
+mod service;
+mod small;
+mod softmax;
+mod spread;
+mod static_table;
+mod stats;
+mod stock;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+mod survey;
+pub mod testing;
+mod text;
+mod tiered;
+mod time_weighted;
+mod topk;
+mod transform;
+mod views;
+mod weighted_select;
+
+pub use annealing::{Annealed, ExponentialSchedule, LinearSchedule, Schedule};
+pub use approx::ApproxDistribution;
+#[cfg(feature = "arbitrary")]
+pub use arbitrary_support::DistributionOp;
+pub use bayes::BetaWeighted;
+pub use bins::Bin;
+pub use bytes::{ByteValue, DecodeError, ValueError};
+pub use consistent::ConsistentSelector;
+pub use contextual::ContextualDistribution;
+pub use convert::DistributionError;
+pub use deterministic::{DeterministicDistribution, SplitMix64};
+pub use diff::{DistributionDiff, WeightChange};
+#[cfg(feature = "macros")]
+pub use distribution_select_macros::static_distribution;
+pub use dynamic::DynamicDistribution;
+pub use export::ExportOptions;
+pub use health::HealthTracker;
+pub use index::IndexDistribution;
+pub use instrumented::{Instrumented, PickReport, ReportLine};
+pub use interleave::{interleave_weighted, interleave_weighted_with, InterleaveWeighted};
+pub use interval::IntervalDistribution;
+pub use journal::Journaled;
+pub use loot::{LootEntry, LootTable};
+pub use markov::MarkovChain;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapDistribution;
+pub use observer::DistributionObserver;
+pub use paced::PacedSampler;
+pub use piecewise::PiecewiseLinear;
+pub use pity::{PityDistribution, PityRule};
+#[cfg(feature = "polars")]
+pub use polars_support::FrameValue;
+pub use ranges::RangeDistribution;
+pub use ranking::MedianPolicy;
+pub use recency::{NonRepeatingSampler, RecencySampler};
+pub use replay::{PickRecord, PickSink, Recorder};
+pub use reservoir::WeightedReservoir;
+pub use round_robin::{DeficitRoundRobin, WeightedRoundRobin};
+pub use service::SamplerHandle;
+pub use small::{SmallDistribution, SMALL_CAPACITY};
+pub use softmax::SoftmaxSampler;
+pub use static_table::{StaticDistribution, StaticTable};
+pub use stock::StockedDistribution;
+pub use survey::{horvitz_thompson_estimate, HorvitzThompsonEstimate};
+pub use text::{FromLinesError, LineError};
+pub use tiered::TieredDistribution;
+pub use time_weighted::TimeWeighted;
+pub use topk::TopKCounter;
+pub use views::{ConditionalView, Excluding, Overrides};
+pub use weighted_select::WeightedSelect;
+
+/// A distribution's weights at some point in time, as captured by `Distribution::snapshot()`.
+/// It shares the distribution's tables rather than copying them.
+#[derive(Clone, Debug)]
+pub struct Snapshot<T> {
+    starts: Arc<Vec<f64>>,
+    values: Arc<Vec<T>>,
+    total_weight: f64,
+    originals: Arc<BTreeMap<T, f64>>,
+    insertion: Arc<BTreeMap<T, u64>>,
+    next_insertion: u64
+}
+
+// With the `tracing` feature, one pick in this many is reported as a trace event.
+#[cfg(feature = "tracing")]
+const TRACE_PICK_INTERVAL: u64 = 1024;
+#[cfg(feature = "tracing")]
+static PICKS_SEEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
 
 #[derive(Clone, Debug)]
 pub struct Distribution<T> {
-    distro: BTreeMap<OrderedFloat<f64>, T>,
+    // The cumulative table as parallel arrays: values[i] covers [starts[i], starts[i + 1]).
+    // Keeping the starts contiguous lets a pick's binary search read only that array.
+    // Each table sits behind an Arc, so clones and snapshots share it until one side writes; a
+    // write goes through Arc::make_mut(), which copies the table only while it is shared.
+    starts: Arc<Vec<f64>>,
+    values: Arc<Vec<T>>,
     total_weight: f64,
-    originals: BTreeMap<T, f64>
+    originals: Arc<BTreeMap<T, f64>>,
+    // When each value was first added, for iter_insertion_order(). rebuild() keeps it in step
+    // with `originals`, so code that edits `originals` directly and then rebuilds needs no
+    // extra bookkeeping.
+    insertion: Arc<BTreeMap<T, u64>>,
+    next_insertion: u64,
+    observer: ObserverSlot<T>
 }
 
 impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
     pub fn new() -> Self {
-        Distribution {distro: BTreeMap::new(), total_weight: 0.0, originals: BTreeMap::new()}
+        Distribution {starts: Arc::default(), values: Arc::default(), total_weight: 0.0, originals: Arc::default(), insertion: Arc::default(), next_insertion: 0, observer: ObserverSlot(None)}
     }
 
     pub fn is_empty(&self) -> bool {
         self.total_weight == 0.0
     }
 
+    /// Adding a value that is already present replaces its weight. Panics unless `weight` is
+    /// positive and finite, the rule every way of building a distribution follows.
     pub fn add(&mut self, value: &T, weight: f64) {
-        assert!(weight > 0.0);
-        self.distro.insert(OrderedFloat(self.total_weight), value.clone());
-        self.total_weight += weight;
-        self.originals.insert(value.clone(), weight);
+        assert!(weight.is_finite() && weight > 0.0);
+        if Arc::make_mut(&mut self.originals).insert(value.clone(), weight).is_some() {
+            self.rebuild();
+        } else {
+            Arc::make_mut(&mut self.starts).push(self.total_weight);
+            Arc::make_mut(&mut self.values).push(value.clone());
+            self.total_weight += weight;
+            Arc::make_mut(&mut self.insertion).insert(value.clone(), self.next_insertion);
+            self.next_insertion += 1;
+        }
+        if let Some(observer) = &self.observer.0 {
+            observer.on_add(value, weight);
+        }
+    }
+
+    /// Adds every pair, replacing the weights of values already present, and rebuilds the
+    /// cumulative table once at the end. All weights are checked before anything is added.
+    pub fn add_all<I:IntoIterator<Item=(T, f64)>>(&mut self, pairs: I) {
+        let pairs: Vec<(T, f64)> = pairs.into_iter().collect();
+        assert!(pairs.iter().all(|(_, weight)| weight.is_finite() && *weight > 0.0));
+        let (originals, insertion) = (Arc::make_mut(&mut self.originals), Arc::make_mut(&mut self.insertion));
+        for (value, weight) in pairs.iter() {
+            originals.insert(value.clone(), *weight);
+            if !insertion.contains_key(value) {
+                insertion.insert(value.clone(), self.next_insertion);
+                self.next_insertion += 1;
+            }
+        }
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in pairs.iter() {
+                observer.on_add(value, *weight);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<f64> {
+        if !self.originals.contains_key(value) {
+            return None;
+        }
+        let weight = Arc::make_mut(&mut self.originals).remove(value)?;
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            observer.on_remove(value, weight);
+        }
+        Some(weight)
+    }
+
+    pub fn get(&self, value: &T) -> Option<f64> {
+        self.originals.get(value).copied()
+    }
+
+    /// Each value with its weight, in ascending value order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, f64)> {
+        self.originals.iter().map(|(value, weight)| (value, *weight))
+    }
+
+    /// Each value with its weight, in the order the values were first added. Replacing a
+    /// weight keeps a value's place; removing and re-adding it moves it to the end. Values
+    /// added in bulk, such as through `From`, count as added in ascending order.
+    pub fn iter_insertion_order(&self) -> impl Iterator<Item = (&T, f64)> {
+        let mut ordered: Vec<(u64, &T, f64)> = self.originals.iter().map(|(value, weight)| (self.insertion[value], value, *weight)).collect();
+        ordered.sort_by_key(|(sequence, _, _)| *sequence);
+        ordered.into_iter().map(|(_, value, weight)| (value, weight))
+    }
+
+    /// The values in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = &T> {
+        self.originals.keys()
+    }
+
+    /// The weights, in the same order as `keys()`.
+    pub fn weights(&self) -> impl Iterator<Item = f64> + '_ {
+        self.originals.values().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.drain().for_each(drop);
+    }
+
+    /// Removes every value, yielding each with its weight in ascending value order.
+    pub fn drain(&mut self) -> impl Iterator<Item = (T, f64)> {
+        let drained = Arc::unwrap_or_clone(std::mem::take(&mut self.originals));
+        self.starts = Arc::default();
+        self.values = Arc::default();
+        self.insertion = Arc::default();
+        self.total_weight = 0.0;
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in drained.iter() {
+                observer.on_remove(value, *weight);
+            }
+        }
+        drained.into_iter()
+    }
+
+    /// Replaces any current observer. Clones made afterwards share it.
+    pub fn set_observer<O:DistributionObserver<T> + 'static>(&mut self, observer: O) {
+        self.observer = ObserverSlot(Some(Arc::new(observer)));
+    }
+
+    pub fn clear_observer(&mut self) {
+        self.observer = ObserverSlot(None);
+    }
+
+    /// Captures the weights in constant time by sharing the tables with the snapshot. The first
+    /// change afterwards copies whichever tables it touches, so later changes never reach the
+    /// snapshot; `restore()` moves the shared tables back in, also in constant time.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {starts: self.starts.clone(), values: self.values.clone(), total_weight: self.total_weight, originals: self.originals.clone(),
+                  insertion: self.insertion.clone(), next_insertion: self.next_insertion}
+    }
+
+    /// Returns to the weights captured in `snapshot`. The observer, if any, is kept and not notified.
+    pub fn restore(&mut self, snapshot: Snapshot<T>) {
+        self.starts = snapshot.starts;
+        self.values = snapshot.values;
+        self.total_weight = snapshot.total_weight;
+        self.originals = snapshot.originals;
+        self.insertion = snapshot.insertion;
+        self.next_insertion = snapshot.next_insertion;
     }
 
     pub fn random_pick(&self) -> T {
+        self.notify_pick(self.pick_ref(&mut rand::thread_rng())).clone()
+    }
+
+    /// Picks once with every weight replaced by `bias(value, weight)`, without building a new table.
+    pub fn random_pick_with_bias<F:Fn(&T, f64)->f64>(&self, bias: F) -> T {
+        self.random_pick_with_bias_with(bias, &mut rand::thread_rng())
+    }
+
+    pub fn random_pick_with_bias_with<F:Fn(&T, f64)->f64, R:Rng + ?Sized>(&self, bias: F, rng: &mut R) -> T {
+        let biased = self.originals.iter().map(|(value, weight)| (value, bias(value, *weight)));
+        self.notify_pick(pick_weighted(biased, rng).expect("bias left no positive weight")).clone()
+    }
+
+    /// Draws two candidates by weight and returns the one with the lower `score`, such as current
+    /// load. The first candidate wins ties.
+    pub fn pick_best_of_two<F:Fn(&T)->f64>(&self, score: F) -> T {
+        self.pick_best_of_two_with(score, &mut rand::thread_rng())
+    }
+
+    pub fn pick_best_of_two_with<F:Fn(&T)->f64, R:Rng + ?Sized>(&self, score: F, rng: &mut R) -> T {
+        let first = self.pick_ref(rng);
+        let second = self.pick_ref(rng);
+        self.notify_pick(if score(second) < score(first) {second} else {first}).clone()
+    }
+
+    /// Maps a point in `[0, total_weight)` on the cumulative scale to the value whose interval contains it.
+    pub fn value_at(&self, position: f64) -> Option<&T> {
+        if !(0.0..self.total_weight).contains(&position) {
+            return None;
+        }
+        closest_key_below(&self.starts, position).map(|i| &self.values[i])
+    }
+
+    pub fn probabilities(&self) -> impl Iterator<Item = (&T, f64)> {
+        self.originals.iter().map(move |(value, weight)| (value, weight / self.total_weight))
+    }
+
+    /// The probability of each value in `order`, zero for values that are absent.
+    pub fn probability_vector(&self, order: &[T]) -> Vec<f64> {
+        order.iter().map(|value| self.get(value).map_or(0.0, |weight| weight / self.total_weight)).collect()
+    }
+
+    pub fn into_probability_map(self) -> BTreeMap<T, f64> {
+        let total = self.total_weight;
+        Arc::unwrap_or_clone(self.originals).into_iter().map(|(value, weight)| (value, weight / total)).collect()
+    }
+
+    /// Deterministically assigns `key` to a value, splitting the key space according to the weights.
+    /// The assignment depends only on the key and the table, so it is the same on every run and platform.
+    pub fn pick_for_key<K: Hash + ?Sized>(&self, key: &K) -> &T {
+        let mut hasher = StableHasher::new();
+        key.hash(&mut hasher);
+        self.notify_pick(self.value_at_fraction(unit_interval(hasher.finish())))
+    }
+
+    /// Derives a pick purely from `seed`, so the same seed and table always give the same value.
+    pub fn pick_seeded(&self, seed: u64) -> &T {
+        self.notify_pick(self.value_at_fraction(unit_interval(splitmix64(seed))))
+    }
+
+    fn value_at_fraction(&self, fraction: f64) -> &T {
         assert!(!self.is_empty());
-        let mut rng = rand::thread_rng();
-        let key_picked = closest_key_below(&self.distro, rng.gen_range(0.0..self.total_weight));
-        self.distro.get(&key_picked.unwrap()).unwrap().clone()
+        self.value_at(fraction * self.total_weight)
+            .unwrap_or_else(|| self.values.last().unwrap())
+    }
+
+    fn pick_ref<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        assert!(!self.is_empty());
+        self.value_at(rng.gen_range(0.0..self.total_weight)).unwrap()
+    }
+
+    fn notify_pick<'a>(&self, value: &'a T) -> &'a T {
+        if let Some(observer) = &self.observer.0 {
+            observer.on_pick(value);
+        }
+        #[cfg(feature = "tracing")]
+        if PICKS_SEEN.fetch_add(1, std::sync::atomic::Ordering::Relaxed).is_multiple_of(TRACE_PICK_INTERVAL) {
+            tracing::trace!(?value, len = self.originals.len(), total_weight = self.total_weight, "sampled pick");
+        }
+        value
+    }
+
+    // Called by every constructor that builds a whole table at once from outside data:
+    // conversions, decoders, parsers and the softmax. Tables started with `new()` and filled
+    // with `add()` are not reported.
+    fn trace_constructed(&self, _source: &'static str) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(source = _source, len = self.originals.len(), total_weight = self.total_weight, "distribution constructed");
+    }
+
+    // Swaps in a whole new set of weights, then tells the observer about every value that
+    // was removed, added or reweighted.
+    fn replace_weights(&mut self, weights: BTreeMap<T, f64>) {
+        let previous = std::mem::replace(&mut self.originals, Arc::new(weights));
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in previous.iter() {
+                if !self.originals.contains_key(value) {
+                    observer.on_remove(value, *weight);
+                }
+            }
+            for (value, weight) in self.originals.iter() {
+                if previous.get(value) != Some(weight) {
+                    observer.on_add(value, *weight);
+                }
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("rebuild", len = self.originals.len()).entered();
+        let mut starts = Vec::with_capacity(self.originals.len());
+        let mut values = Vec::with_capacity(self.originals.len());
+        self.total_weight = 0.0;
+        for (value, weight) in self.originals.iter() {
+            starts.push(self.total_weight);
+            values.push(value.clone());
+            self.total_weight += weight;
+        }
+        self.starts = Arc::new(starts);
+        self.values = Arc::new(values);
+        if self.insertion.len() != self.originals.len() || !self.insertion.keys().eq(self.originals.keys()) {
+            let insertion = Arc::make_mut(&mut self.insertion);
+            insertion.retain(|value, _| self.originals.contains_key(value));
+            for value in self.originals.keys() {
+                if !insertion.contains_key(value) {
+                    insertion.insert(value.clone(), self.next_insertion);
+                    self.next_insertion += 1;
+                }
+            }
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(total_weight = self.total_weight, "rebuilt cumulative table");
     }
 
     pub fn distro_with<F:Fn(&T)->bool>(&self, keep: F) -> Self {
@@ -45,15 +448,61 @@ impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
     }
 }
 
-fn closest_key_below<T>(tree: &BTreeMap<OrderedFloat<f64>, T>, target: f64) -> Option<OrderedFloat<f64>> {
-    tree.range(..=OrderedFloat(target)).rev().next().map(|(k,_)| *k)
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for Distribution<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Panics if `value` is absent; see `get()`.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Index<&T> for Distribution<T> {
+    type Output = f64;
+
+    fn index(&self, value: &T) -> &f64 {
+        &self.originals[value]
+    }
+}
+
+/// Picks one item in proportion to its weight, in a single pass and without building a `Distribution`.
+/// Zero-weight items are never picked; returns `None` if no item has positive weight.
+pub fn pick_weighted<T, I: IntoIterator<Item=(T, f64)>, R: Rng + ?Sized>(iter: I, rng: &mut R) -> Option<T> {
+    let mut total = 0.0;
+    let mut picked = None;
+    for (value, weight) in iter {
+        assert!(weight.is_finite() && weight >= 0.0);
+        if weight > 0.0 {
+            total += weight;
+            if rng.gen_range(0.0..total) < weight {
+                picked = Some(value);
+            }
+        }
+    }
+    picked
+}
+
+// Picks from a table of running totals, where ends[i] is the total weight of entries 0..=i,
+// as `IndexDistribution` and `StaticTable` keep them. Entries with zero weight are never picked.
+fn pick_from_ends<R:Rng + ?Sized>(ends: &[f64], rng: &mut R) -> usize {
+    let target = rng.gen_range(0.0..ends[ends.len() - 1]);
+    ends.partition_point(|end| *end <= target).min(ends.len() - 1)
+}
+
+// The weight of entry `index` in a table of running totals.
+fn weight_from_ends(ends: &[f64], index: usize) -> Option<f64> {
+    let end = *ends.get(index)?;
+    Some(if index == 0 {end} else {end - ends[index - 1]})
+}
+
+// The index of the last start no greater than `target`.
+fn closest_key_below(starts: &[f64], target: f64) -> Option<usize> {
+    starts.partition_point(|start| *start <= target).checked_sub(1)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeMap;
+    use std::{collections::BTreeMap, sync::Arc};
     use ordered_float::OrderedFloat;
-    use crate::{closest_key_below, Distribution};
+    use crate::{closest_key_below, pick_weighted, Distribution, SplitMix64};
     use hash_histogram::HashHistogram;
 
     fn input_data() -> BTreeMap<OrderedFloat<f64>, String> {
@@ -74,13 +523,14 @@ mod tests {
  
     #[test]
     fn test_closest_key_below() {
-        let t = input_data();
-        assert_eq!(closest_key_below(&t, 0.6).unwrap(), 0.5);
-        assert_eq!(closest_key_below(&t, 1.0).unwrap(), 1.0);
-        assert_eq!(closest_key_below(&t, 1.00001).unwrap(), 1.0);
-        assert_eq!(closest_key_below(&t, 10.0).unwrap(), 4.8);
-        assert_eq!(closest_key_below(&t, 4.1).unwrap(), 3.5);
-        assert_eq!(closest_key_below(&t, 3.4).unwrap(), 1.0);
+        let t: Vec<f64> = input_data().keys().map(|k| k.into_inner()).collect();
+        assert_eq!(t[closest_key_below(&t, 0.6).unwrap()], 0.5);
+        assert_eq!(t[closest_key_below(&t, 1.0).unwrap()], 1.0);
+        assert_eq!(t[closest_key_below(&t, 1.00001).unwrap()], 1.0);
+        assert_eq!(t[closest_key_below(&t, 10.0).unwrap()], 4.8);
+        assert_eq!(t[closest_key_below(&t, 4.1).unwrap()], 3.5);
+        assert_eq!(t[closest_key_below(&t, 3.4).unwrap()], 1.0);
+        assert_eq!(closest_key_below(&t, 0.4), None);
     }
 
     #[test]
@@ -107,8 +557,245 @@ mod tests {
 
     #[test]
     fn test_with() {
-        let dist = example_dist().distro_with(|s| s.chars().next().unwrap() as usize % 2 == 0);
+        let dist = example_dist().distro_with(|s| (s.chars().next().unwrap() as usize).is_multiple_of(2));
         let matched = num_match_target(&dist, 20, 200, vec!["d".to_owned(), "b".to_owned()]);
         assert_eq!(matched, 20);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_pick_weighted() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(pick_weighted(Vec::<(char, f64)>::new(), &mut rng), None);
+        assert_eq!(pick_weighted([('a', 0.0), ('b', 2.0), ('c', 0.0)], &mut rng), Some('b'));
+
+        let mut counts = HashHistogram::new();
+        for _ in 0..2000 {
+            counts.bump(&pick_weighted(input_data().into_iter().map(|(w, v)| (v, w.into_inner())), &mut rng).unwrap());
+        }
+        assert_eq!(counts.ranking()[0], "d");
+    }
+
+    #[test]
+    fn test_value_at() {
+        let dist = example_dist();
+        assert_eq!(dist.value_at(0.0).unwrap(), "b");
+        assert_eq!(dist.value_at(0.49).unwrap(), "b");
+        assert_eq!(dist.value_at(0.5).unwrap(), "a");
+        assert_eq!(dist.value_at(9.79).unwrap(), "d");
+        assert_eq!(dist.value_at(9.8), None);
+        assert_eq!(dist.value_at(-0.1), None);
+        assert_eq!(dist.value_at(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let dist = example_dist();
+        let probs: Vec<(&String, f64)> = dist.probabilities().collect();
+        assert_eq!(probs[0], (&"a".to_string(), 1.0 / 9.8));
+        assert!((probs.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let map = dist.clone().into_probability_map();
+        assert_eq!(map.len(), 4);
+        assert_eq!(map["d"], 4.8 / 9.8);
+    }
+
+    #[test]
+    fn test_probability_vector() {
+        let dist = example_dist();
+        let order: Vec<String> = ["d", "z", "b"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(dist.probability_vector(&order), vec![4.8 / 9.8, 0.0, 0.5 / 9.8]);
+    }
+
+    #[test]
+    fn test_pick_for_key() {
+        let dist = example_dist();
+        for user in 0..50 {
+            assert_eq!(dist.pick_for_key(&user), dist.pick_for_key(&user));
+        }
+        let mut counts = HashHistogram::new();
+        for user in 0..5000u32 {
+            counts.bump(dist.pick_for_key(&user));
+        }
+        assert_eq!(counts.ranking(), vec!["d".to_owned(), "c".to_owned(), "a".to_owned(), "b".to_owned()]);
+    }
+
+    #[test]
+    fn test_pick_best_of_two() {
+        let dist = example_dist();
+        let load = |s: &String| if s == "d" {10.0} else {1.0};
+        let d_count = (0..1000).filter(|_| dist.pick_best_of_two(load) == "d").count();
+        let d_share = 4.8 / 9.8;
+        let expected = d_share * d_share * 1000.0;
+        assert!((d_count as f64 - expected).abs() < 100.0);
+        assert_eq!(dist.distro_with(|s| s == "a").pick_best_of_two(load), "a");
+        let seeded = |seed| dist.pick_best_of_two_with(load, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(12), seeded(12));
+    }
+
+    #[test]
+    fn test_random_pick_with_bias() {
+        let dist = example_dist();
+        let luck = |s: &String, w: f64| if s == "b" {w * 1000.0} else {w};
+        let b_count = (0..200).filter(|_| dist.random_pick_with_bias(luck) == "b").count();
+        assert!(b_count > 180);
+        assert!((0..50).all(|_| dist.random_pick_with_bias(|s, w| if s == "a" {w} else {0.0}) == "a"));
+        let seeded = |seed| dist.random_pick_with_bias_with(luck, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(12), seeded(12));
+    }
+
+    #[test]
+    fn test_pick_seeded() {
+        let dist = example_dist();
+        let chunk_seed = |x: u64, y: u64| x.wrapping_mul(0x1000193) ^ y;
+        assert_eq!(dist.pick_seeded(chunk_seed(3, 4)), dist.pick_seeded(chunk_seed(3, 4)));
+        let mut counts = HashHistogram::new();
+        for seed in 0..5000 {
+            counts.bump(dist.pick_seeded(seed));
+        }
+        assert_eq!(counts.ranking(), vec!["d".to_owned(), "c".to_owned(), "a".to_owned(), "b".to_owned()]);
+    }
+
+    #[test]
+    fn test_add_existing_and_remove() {
+        let mut dist = example_dist();
+        dist.add(&"a".to_owned(), 2.0);
+        assert_eq!(dist.total_weight, 10.8);
+        assert_eq!(dist.values.len(), 4);
+        assert_eq!(dist.remove(&"c".to_owned()), Some(3.5));
+        assert_eq!(dist.total_weight, 7.3);
+        assert_eq!(dist.value_at(2.0).unwrap(), "b");
+        assert_eq!(dist.remove(&"z".to_owned()), None);
+    }
+
+    #[test]
+    fn test_add_all() {
+        let mut dist = example_dist();
+        dist.add_all([("e".to_owned(), 2.0), ("a".to_owned(), 3.0), ("f".to_owned(), 0.2)]);
+        assert_eq!(dist.get(&"a".to_owned()), Some(3.0));
+        assert!((dist.total_weight - 14.0).abs() < 1e-10);
+        assert_eq!(dist.values.len(), 6);
+        assert_eq!(dist.iter_insertion_order().map(|(value, _)| value.as_str()).collect::<String>(), "bacdef");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_all_rejects_nonpositive() {
+        let mut dist = example_dist();
+        dist.add_all([("e".to_owned(), 2.0), ("f".to_owned(), 0.0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_rejects_infinite() {
+        example_dist().add(&"e".to_owned(), f64::INFINITY);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{span, Event, Metadata, Subscriber};
+
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {true}
+            fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+                self.0.lock().unwrap().push(format!("span {}", attrs.metadata().name()));
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                self.0.lock().unwrap().push(format!("event {}", event.metadata().level()));
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Recorder(seen.clone()), || {
+            let mut dist = Distribution::from_bytes(&example_dist().to_bytes()).unwrap();
+            dist.remove(&"a".to_owned());
+        });
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0], "event DEBUG");
+        assert!(seen.contains(&"span rebuild".to_owned()));
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut dist = example_dist();
+        let saved = dist.snapshot();
+        dist.add(&"e".to_owned(), 100.0);
+        dist.remove(&"a".to_owned());
+        dist.restore(saved);
+        assert_eq!(dist.total_weight, 9.8);
+        assert_eq!(dist.originals, example_dist().originals);
+        assert_eq!(dist.value_at(0.5).unwrap(), "a");
+
+        let saved = dist.snapshot();
+        assert!(Arc::ptr_eq(&saved.starts, &dist.starts) && Arc::ptr_eq(&saved.originals, &dist.originals));
+        dist.add(&"e".to_owned(), 1.0);
+        assert!(!Arc::ptr_eq(&saved.originals, &dist.originals));
+        assert_eq!(saved.originals.len(), 4);
+        assert_eq!(saved.starts.len(), 4);
+    }
+
+    #[test]
+    fn test_clear_and_drain() {
+        let mut dist = example_dist();
+        let drained: Vec<(String, f64)> = dist.drain().collect();
+        assert_eq!(drained[0], ("a".to_owned(), 1.0));
+        assert_eq!(drained.len(), 4);
+        assert!(dist.is_empty() && dist.values.is_empty() && dist.originals.is_empty());
+
+        dist.add(&"e".to_owned(), 2.0);
+        assert_eq!(dist.random_pick(), "e");
+        dist.clear();
+        assert!(dist.is_empty() && dist.values.is_empty());
+    }
+
+    #[test]
+    fn test_get_and_index() {
+        let dist = example_dist();
+        assert_eq!(dist.get(&"c".to_owned()), Some(3.5));
+        assert_eq!(dist.get(&"z".to_owned()), None);
+        assert_eq!(dist[&"d".to_owned()], 4.8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_missing() {
+        let _ = example_dist()[&"z".to_owned()];
+    }
+
+    #[test]
+    fn test_keys_and_weights() {
+        let dist = example_dist();
+        assert_eq!(dist.keys().cloned().collect::<Vec<_>>(), vec!["a", "b", "c", "d"]);
+        assert_eq!(dist.weights().collect::<Vec<_>>(), vec![1.0, 0.5, 3.5, 4.8]);
+    }
+
+    #[test]
+    fn test_iteration_orders() {
+        let mut dist = Distribution::new();
+        for (value, weight) in [('c', 1.0), ('a', 2.0), ('b', 3.0)] {
+            dist.add(&value, weight);
+        }
+        assert_eq!(dist.iter().map(|(value, _)| *value).collect::<String>(), "abc");
+        assert_eq!(dist.iter_insertion_order().map(|(value, _)| *value).collect::<String>(), "cab");
+
+        dist.add(&'c', 5.0);
+        dist.remove(&'a');
+        dist.add(&'a', 1.0);
+        assert_eq!(dist.iter_insertion_order().collect::<Vec<_>>(), vec![(&'c', 5.0), (&'b', 3.0), (&'a', 1.0)]);
+
+        let saved = dist.snapshot();
+        dist.truncate_below(2.0);
+        dist.add(&'d', 1.0);
+        assert_eq!(dist.iter_insertion_order().map(|(value, _)| *value).collect::<String>(), "cbd");
+        dist.restore(saved);
+        assert_eq!(dist.iter_insertion_order().map(|(value, _)| *value).collect::<String>(), "cba");
+    }
+}
@@ -0,0 +1,112 @@
+use rand::Rng;
+use crate::Distribution;
+
+#[derive(Clone, Debug)]
+pub enum LootEntry<T> {
+    Item(T),
+    Table(LootTable<T>),
+    Nothing
+}
+
+/// A drop table that rolls `rolls` times, where each roll picks one entry by weight. An entry
+/// picked with a count of `n` yields `n` copies of its item, or rolls its nested table `n` times.
+#[derive(Clone, Debug)]
+pub struct LootTable<T> {
+    rolls: usize,
+    entries: Vec<(LootEntry<T>, usize)>,
+    weights: Distribution<usize>
+}
+
+impl <T:Clone> LootTable<T> {
+    pub fn new(rolls: usize) -> Self {
+        LootTable {rolls, entries: Vec::new(), weights: Distribution::new()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn add_item(&mut self, value: T, weight: f64, count: usize) {
+        self.add_entry(LootEntry::Item(value), weight, count);
+    }
+
+    pub fn add_table(&mut self, table: LootTable<T>, weight: f64, count: usize) {
+        self.add_entry(LootEntry::Table(table), weight, count);
+    }
+
+    pub fn add_nothing(&mut self, weight: f64) {
+        self.add_entry(LootEntry::Nothing, weight, 1);
+    }
+
+    pub fn add_entry(&mut self, entry: LootEntry<T>, weight: f64, count: usize) {
+        self.weights.add(&self.entries.len(), weight);
+        self.entries.push((entry, count));
+    }
+
+    pub fn roll(&self) -> Vec<T> {
+        self.roll_with(&mut rand::thread_rng())
+    }
+
+    /// As `roll()`, drawing every roll, nested ones included, from `rng`.
+    pub fn roll_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<T> {
+        let mut bag = Vec::new();
+        self.roll_into(rng, &mut bag);
+        bag
+    }
+
+    fn roll_into<R: Rng + ?Sized>(&self, rng: &mut R, bag: &mut Vec<T>) {
+        if self.is_empty() {
+            return;
+        }
+        for _ in 0..self.rolls {
+            let (entry, count) = &self.entries[*self.weights.pick_ref(rng)];
+            match entry {
+                LootEntry::Item(value) => bag.extend(std::iter::repeat_n(value, *count).cloned()),
+                LootEntry::Table(table) => (0..*count).for_each(|_| table.roll_into(rng, bag)),
+                LootEntry::Nothing => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LootTable, SplitMix64};
+
+    #[test]
+    fn test_nested_roll() {
+        let mut gems = LootTable::new(1);
+        gems.add_item("ruby", 1.0, 1);
+        gems.add_item("emerald", 1.0, 1);
+
+        let mut chest = LootTable::new(2);
+        chest.add_item("gold", 1.0, 10);
+        chest.add_table(gems, 1.0, 3);
+
+        for _ in 0..50 {
+            let bag = chest.roll();
+            let gold = bag.iter().filter(|i| **i == "gold").count();
+            let gems = bag.iter().filter(|i| **i == "ruby" || **i == "emerald").count();
+            assert_eq!(gold % 10, 0);
+            assert_eq!(gold / 10 + gems / 3, 2);
+        }
+
+        let seeded = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..10).map(|_| chest.roll_with(&mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(seeded(4), seeded(4));
+    }
+
+    #[test]
+    fn test_nothing_and_empty() {
+        let mut table = LootTable::<&str>::new(5);
+        table.add_nothing(1.0);
+        assert!(table.roll().is_empty());
+        assert!(LootTable::<u8>::new(3).roll().is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::Rng;
+use crate::Distribution;
+
+/// A Markov chain keeping one next-state distribution per state.
+#[derive(Clone, Debug)]
+pub struct MarkovChain<T> {
+    transitions: BTreeMap<T, Distribution<T>>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> MarkovChain<T> {
+    pub fn new() -> Self {
+        MarkovChain {transitions: BTreeMap::new()}
+    }
+
+    /// Counts one observed transition from `from` to `to`.
+    pub fn observe(&mut self, from: &T, to: &T) {
+        let next = self.transitions.entry(from.clone()).or_default();
+        let count = next.originals.get(to).copied().unwrap_or(0.0);
+        next.add(to, count + 1.0);
+    }
+
+    pub fn set_transition(&mut self, from: &T, to: &T, weight: f64) {
+        self.transitions.entry(from.clone()).or_default().add(to, weight);
+    }
+
+    /// The next-state distribution of `state`, if it has any outgoing transitions.
+    pub fn transitions(&self, state: &T) -> Option<&Distribution<T>> {
+        self.transitions.get(state)
+    }
+
+    /// Every state appearing on either side of a transition.
+    pub fn states(&self) -> Vec<T> {
+        let mut states: Vec<T> = self.transitions.iter()
+            .flat_map(|(from, next)| std::iter::once(from).chain(next.originals.keys()))
+            .cloned()
+            .collect();
+        states.sort();
+        states.dedup();
+        states
+    }
+
+    /// A state is absorbing if the chain can never leave it.
+    pub fn is_absorbing(&self, state: &T) -> bool {
+        self.transitions.get(state).is_none_or(|next| next.originals.keys().all(|to| to == state))
+    }
+
+    /// Returns `None` if `state` has no outgoing transitions.
+    pub fn step(&self, state: &T) -> Option<T> {
+        self.step_with(state, &mut rand::thread_rng())
+    }
+
+    pub fn step_with<R:Rng + ?Sized>(&self, state: &T, rng: &mut R) -> Option<T> {
+        self.transitions.get(state).map(|next| next.notify_pick(next.pick_ref(rng)).clone())
+    }
+
+    /// Up to `n` successive states after `start`, stopping early at an absorbing state.
+    pub fn walk(&self, start: &T, n: usize) -> Vec<T> {
+        self.walk_with(start, n, &mut rand::thread_rng())
+    }
+
+    pub fn walk_with<R:Rng + ?Sized>(&self, start: &T, n: usize, rng: &mut R) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut state = start.clone();
+        while result.len() < n && !self.is_absorbing(&state) {
+            state = self.step_with(&state, rng).unwrap();
+            result.push(state.clone());
+        }
+        result
+    }
+
+    /// Long-run state frequencies, found by power iteration until no probability moves by more
+    /// than `tolerance` in total. Each step is averaged with the previous one, which keeps
+    /// periodic chains from oscillating without changing the answer. For chains with several
+    /// absorbing classes the result depends on the uniform starting point.
+    pub fn stationary(&self, tolerance: f64) -> Distribution<T> {
+        assert!(tolerance > 0.0);
+        let states = self.states();
+        let index: BTreeMap<&T, usize> = states.iter().enumerate().map(|(i, state)| (state, i)).collect();
+        let rows: Vec<Vec<(usize, f64)>> = states.iter().map(|state| match self.transitions.get(state) {
+            Some(next) => next.originals.iter().map(|(to, weight)| (index[to], weight / next.total_weight)).collect(),
+            None => vec![(index[state], 1.0)]
+        }).collect();
+
+        let mut current = vec![1.0 / states.len() as f64; states.len()];
+        for _ in 0..MAX_STATIONARY_ITERATIONS {
+            let mut next: Vec<f64> = current.iter().map(|p| p / 2.0).collect();
+            for (from, row) in rows.iter().enumerate() {
+                for (to, probability) in row.iter() {
+                    next[*to] += current[from] * probability / 2.0;
+                }
+            }
+            let change: f64 = next.iter().zip(current.iter()).map(|(a, b)| (a - b).abs()).sum();
+            current = next;
+            if change < tolerance {
+                break;
+            }
+        }
+
+        let mut result = Distribution::new();
+        for (state, probability) in states.iter().zip(current) {
+            if probability > 0.0 {
+                result.add(state, probability);
+            }
+        }
+        result
+    }
+}
+
+const MAX_STATIONARY_ITERATIONS: usize = 1_000_000;
+
+// An order-n chain has the last n values as its state, so every transition shifts the
+// window by one value.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> MarkovChain<Vec<T>> {
+    pub fn train_from_sequence<I:IntoIterator<Item=T>>(order: usize, sequence: I) -> Self {
+        let mut chain = MarkovChain::new();
+        chain.observe_sequence(order, sequence);
+        chain
+    }
+
+    /// Counts every transition between consecutive windows of `order` values in `sequence`.
+    pub fn observe_sequence<I:IntoIterator<Item=T>>(&mut self, order: usize, sequence: I) {
+        assert!(order > 0);
+        let mut window = Vec::with_capacity(order + 1);
+        for value in sequence {
+            window.push(value);
+            if window.len() > order {
+                self.observe(&window[..order].to_vec(), &window[1..].to_vec());
+                window.remove(0);
+            }
+        }
+    }
+
+    /// Up to `n` values following the context `start`, whose length is the order of the chain.
+    pub fn generate(&self, start: &[T], n: usize) -> Vec<T> {
+        self.walk(&start.to_vec(), n).into_iter().filter_map(|mut window| window.pop()).collect()
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for MarkovChain<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MarkovChain, SplitMix64};
+
+    #[test]
+    fn test_observe_and_walk() {
+        let mut chain = MarkovChain::new();
+        chain.observe(&'a', &'b');
+        chain.observe(&'a', &'b');
+        chain.observe(&'a', &'a');
+        chain.observe(&'b', &'a');
+        chain.observe(&'b', &'c');
+        chain.set_transition(&'c', &'c', 1.0);
+        assert_eq!(chain.transitions(&'a').unwrap().originals[&'b'], 2.0);
+        assert_eq!(chain.states(), vec!['a', 'b', 'c']);
+        assert!(!chain.is_absorbing(&'a'));
+        assert!(chain.is_absorbing(&'c'));
+        assert!(chain.is_absorbing(&'z'));
+        assert_eq!(chain.step(&'z'), None);
+
+        let walk = chain.walk(&'a', 10_000);
+        assert_eq!(walk.last(), Some(&'c'));
+        assert!(walk.iter().take(walk.len() - 1).all(|state| *state != 'c'));
+        assert_eq!(chain.walk(&'a', 0), vec![]);
+
+        let seeded = |seed| chain.walk_with(&'a', 50, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(5), seeded(5));
+    }
+
+    #[test]
+    fn test_train_from_sequence() {
+        let chain = MarkovChain::train_from_sequence(2, "abcabd".chars());
+        let next = chain.transitions(&vec!['a', 'b']).unwrap();
+        assert_eq!(next.originals[&vec!['b', 'c']], 1.0);
+        assert_eq!(next.originals[&vec!['b', 'd']], 1.0);
+        assert!(chain.is_absorbing(&vec!['b', 'd']));
+
+        let generated = chain.generate(&['b', 'c'], 100);
+        assert_eq!(&generated[..2], &['a', 'b']);
+        assert!(generated.len() < 100 && generated.ends_with(&['d']));
+    }
+
+    #[test]
+    fn test_stationary() {
+        let mut chain = MarkovChain::new();
+        chain.set_transition(&'a', &'a', 0.9);
+        chain.set_transition(&'a', &'b', 0.1);
+        chain.set_transition(&'b', &'a', 0.5);
+        chain.set_transition(&'b', &'b', 0.5);
+        let stationary = chain.stationary(1e-12);
+        assert!((stationary.originals[&'a'] - 5.0 / 6.0).abs() < 1e-9);
+        assert!((stationary.originals[&'b'] - 1.0 / 6.0).abs() < 1e-9);
+
+        let mut periodic = MarkovChain::new();
+        periodic.set_transition(&0, &1, 1.0);
+        periodic.set_transition(&1, &0, 1.0);
+        let stationary = periodic.stationary(1e-12);
+        assert!((stationary.originals[&0] - 0.5).abs() < 1e-9);
+    }
+}
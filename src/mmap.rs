@@ -0,0 +1,256 @@
+// File layout (little-endian), written by `Distribution::write_mmap_table()`:
+//   version: u8, 7 bytes padding
+//   count:   u64
+//   total:   f64
+//   count x (cumulative start: f64, value offset: u64), in ascending start order
+//   value encodings, with offsets relative to the start of this section
+
+use std::{cmp::Ordering, fmt::Debug, fs::File, io::{self, Write}, marker::PhantomData, path::Path};
+use memmap2::Mmap;
+use rand::Rng;
+use crate::{ByteValue, Distribution};
+
+const MMAP_VERSION: u8 = 1;
+const HEADER_LEN: usize = 24;
+const INDEX_ENTRY_LEN: usize = 16;
+
+/// A read-only distribution sampled directly from a memory-mapped table file. Opening checks only
+/// the header and the file length, so it takes constant time however large the table is; use
+/// `open_checked()` for files that might not have been written by `write_mmap_table()`.
+pub struct MmapDistribution<T> {
+    map: Mmap,
+    count: usize,
+    total_weight: f64,
+    _values: PhantomData<T>
+}
+
+impl <T:ByteValue> MmapDistribution<T> {
+    /// Maps the table at `path` without reading its index. A corrupt index can make picks panic
+    /// or favour the wrong values.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the table is treated as read-only; callers must not modify the file while it is mapped.
+        let map = unsafe {Mmap::map(&file)?};
+        if map.len() < HEADER_LEN {
+            return Err(invalid_data("file is shorter than the table header"));
+        }
+        if map[0] != MMAP_VERSION {
+            return Err(invalid_data("unsupported table version"));
+        }
+        let count = usize::try_from(read_u64(&map, 8)).map_err(|_| invalid_data("entry count too large"))?;
+        let total_weight = read_f64(&map, 16);
+        let index_end = count.checked_mul(INDEX_ENTRY_LEN).and_then(|n| n.checked_add(HEADER_LEN));
+        if index_end.is_none_or(|end| end > map.len()) {
+            return Err(invalid_data("file is shorter than its index"));
+        }
+        if count > 0 && !(total_weight.is_finite() && total_weight > 0.0) {
+            return Err(invalid_data("total weight is not positive and finite"));
+        }
+        Ok(MmapDistribution {map, count, total_weight, _values: PhantomData})
+    }
+
+    /// Like `open()`, then `validate()`, so that nothing read from the table can fail later.
+    pub fn open_checked<P: AsRef<Path>>(path: P) -> io::Result<Self> where T: Ord {
+        let table = Self::open(path)?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Reads the whole index and every value, in time and allocations linear in the table:
+    /// the starts must rise from zero without passing the total, every offset must lead to a
+    /// value that decodes within the file, and the values must be in ascending order.
+    pub fn validate(&self) -> io::Result<()> where T: Ord {
+        let values_start = HEADER_LEN + self.count * INDEX_ENTRY_LEN;
+        let mut previous: Option<(f64, T)> = None;
+        for i in 0..self.count {
+            let entry = HEADER_LEN + i * INDEX_ENTRY_LEN;
+            let start = read_f64(&self.map, entry);
+            let lowest = previous.as_ref().map_or(0.0, |(start, _)| *start);
+            if !(start >= lowest && start <= self.total_weight) || (i == 0 && start != 0.0) {
+                return Err(invalid_data("index starts are not ascending from zero within the total"));
+            }
+            let offset = usize::try_from(read_u64(&self.map, entry + 8)).ok();
+            let encoded = offset.and_then(|offset| values_start.checked_add(offset)).and_then(|start| self.map.get(start..));
+            let value = encoded.and_then(|bytes| T::read_bytes(bytes).ok())
+                .ok_or_else(|| invalid_data("index entry does not point to a valid value"))?.0;
+            if previous.as_ref().is_some_and(|(_, last)| *last >= value) {
+                return Err(invalid_data("values are not in ascending order"));
+            }
+            previous = Some((start, value));
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        assert!(!self.is_empty());
+        let target = rng.gen_range(0.0..self.total_weight);
+        self.value(self.index_below(target))
+    }
+
+    /// The weight of `value`, found by binary search. This relies on the values being in
+    /// ascending order, as `write_mmap_table()` writes them and `validate()` checks; on a table
+    /// that is out of order it can miss values that are present. The weight is recovered from the cumulative starts, so it can differ from the weight that was
+    /// written by a rounding error.
+    pub fn get(&self, value: &T) -> Option<f64> where T: Ord {
+        let (mut low, mut high) = (0, self.count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.value(mid).cmp(value) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => {
+                    let end = if mid + 1 < self.count {self.start(mid + 1)} else {self.total_weight};
+                    return Some(end - self.start(mid));
+                }
+            }
+        }
+        None
+    }
+
+    fn index_below(&self, target: f64) -> usize {
+        let (mut low, mut high) = (0, self.count);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.start(mid) <= target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    fn start(&self, i: usize) -> f64 {
+        read_f64(&self.map, HEADER_LEN + i * INDEX_ENTRY_LEN)
+    }
+
+    fn value(&self, i: usize) -> T {
+        let values_start = HEADER_LEN + self.count * INDEX_ENTRY_LEN;
+        let offset = read_u64(&self.map, HEADER_LEN + i * INDEX_ENTRY_LEN + 8) as usize;
+        let encoded = values_start.checked_add(offset).and_then(|start| self.map.get(start..));
+        encoded.and_then(|bytes| T::read_bytes(bytes).ok()).expect("corrupt value section in mapped table").0
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + ByteValue> Distribution<T> {
+    /// Writes this distribution in the layout read by `MmapDistribution::open()`.
+    pub fn write_mmap_table<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut header = vec![MMAP_VERSION, 0, 0, 0, 0, 0, 0, 0];
+        (self.originals.len() as u64).write_bytes(&mut header);
+        self.total_weight.write_bytes(&mut header);
+        writer.write_all(&header)?;
+
+        let mut scratch = Vec::new();
+        let (mut start, mut offset) = (0.0, 0u64);
+        for (value, weight) in self.originals.iter() {
+            writer.write_all(&f64::to_le_bytes(start))?;
+            writer.write_all(&offset.to_le_bytes())?;
+            scratch.clear();
+            value.write_bytes(&mut scratch);
+            start += weight;
+            offset += scratch.len() as u64;
+        }
+
+        for value in self.originals.keys() {
+            scratch.clear();
+            value.write_bytes(&mut scratch);
+            writer.write_all(&scratch)?;
+        }
+        writer.flush()
+    }
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn read_f64(bytes: &[u8], at: usize) -> f64 {
+    f64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, fs::File, io::{BufWriter, ErrorKind}};
+    use crate::{Distribution, MmapDistribution};
+
+    #[test]
+    fn test_mapped_picks() {
+        let mut dist = Distribution::new();
+        for (value, weight) in [("a", 1.0), ("b", 0.5), ("c", 3.5), ("d", 4.8)] {
+            dist.add(&value.to_string(), weight);
+        }
+        let path = std::env::temp_dir().join(format!("distribution_select_mmap_{}.bin", std::process::id()));
+        dist.write_mmap_table(&mut BufWriter::new(File::create(&path).unwrap())).unwrap();
+
+        let mapped = MmapDistribution::<String>::open(&path).unwrap();
+        assert_eq!(mapped.len(), 4);
+        assert_eq!(mapped.total_weight(), dist.total_weight);
+        for (i, value) in ["a", "b", "c", "d"].iter().enumerate() {
+            assert_eq!(mapped.value(i), *value);
+            assert!((mapped.get(&value.to_string()).unwrap() - dist.get(&value.to_string()).unwrap()).abs() < 1e-12);
+        }
+        assert_eq!(mapped.get(&"e".to_string()), None);
+        assert_eq!(mapped.index_below(0.9), 0);
+        assert_eq!(mapped.index_below(1.4), 1);
+        assert_eq!(mapped.index_below(1.5), 2);
+        assert_eq!(mapped.index_below(9.7), 3);
+
+        let seen: BTreeSet<String> = (0..200).map(|_| mapped.random_pick()).collect();
+        assert_eq!(seen.len(), 4);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_corrupt_index() {
+        let dist: Distribution<String> = vec![("a".to_string(), 1.0), ("b".to_string(), 2.0)].into();
+        let mut table = Vec::new();
+        dist.write_mmap_table(&mut table).unwrap();
+        let path = std::env::temp_dir().join(format!("distribution_select_mmap_corrupt_{}.bin", std::process::id()));
+
+        let mut corruptions: Vec<Vec<u8>> = Vec::new();
+        let mut unsorted = table.clone();
+        unsorted[40..48].copy_from_slice(&(-1.0f64).to_le_bytes());
+        corruptions.push(unsorted);
+        let mut past_total = table.clone();
+        past_total[40..48].copy_from_slice(&5.0f64.to_le_bytes());
+        corruptions.push(past_total);
+        let mut bad_offset = table.clone();
+        bad_offset[48..56].copy_from_slice(&1000u64.to_le_bytes());
+        corruptions.push(bad_offset);
+        corruptions.push(table[..table.len() - 1].to_vec());
+        let mut unordered = table.clone();
+        let first_value = unordered.iter().rposition(|byte| *byte == b'a').unwrap();
+        unordered[first_value] = b'c';
+        corruptions.push(unordered);
+
+        for corrupt in corruptions {
+            std::fs::write(&path, &corrupt).unwrap();
+            assert!(MmapDistribution::<String>::open(&path).is_ok());
+            let error = MmapDistribution::<String>::open_checked(&path).err().expect("corrupt table was accepted");
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+        }
+        std::fs::write(&path, &table).unwrap();
+        assert!(MmapDistribution::<String>::open_checked(&path).is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
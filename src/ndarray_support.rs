@@ -0,0 +1,40 @@
+use std::fmt::Debug;
+use ndarray::{Array1, ArrayView1};
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Pairs `values[i]` with `weights[i]`. Zero weights are skipped; as with `add()`, a
+    /// repeated value keeps its last weight.
+    pub fn from_weights_array(values: &[T], weights: ArrayView1<f64>) -> Self {
+        assert_eq!(values.len(), weights.len());
+        let mut result = Distribution::new();
+        for (value, weight) in values.iter().zip(weights.iter()) {
+            assert!(weight.is_finite() && *weight >= 0.0);
+            if *weight > 0.0 {
+                result.add(value, *weight);
+            }
+        }
+        result.trace_constructed("ndarray");
+        result
+    }
+
+    /// The probability of each value in `order`, as in `probability_vector()`.
+    pub fn to_array(&self, order: &[T]) -> Array1<f64> {
+        Array1::from(self.probability_vector(order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use crate::Distribution;
+
+    #[test]
+    fn test_array_round_trip() {
+        let scores = array![2.0, 0.0, 6.0];
+        let dist = Distribution::from_weights_array(&['a', 'b', 'c'], scores.view());
+        assert_eq!(dist.total_weight, 8.0);
+        assert!(!dist.originals.contains_key(&'b'));
+        assert_eq!(dist.to_array(&['c', 'b', 'a']), array![0.75, 0.0, 0.25]);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fmt::{self, Debug}, sync::Arc};
+
+/// Callbacks invoked by a `Distribution` after picks and mutations. All methods default to doing
+/// nothing, so implementations only override the events they care about.
+pub trait DistributionObserver<T>: Send + Sync {
+    fn on_pick(&self, _value: &T) {}
+
+    fn on_add(&self, _value: &T, _weight: f64) {}
+
+    fn on_remove(&self, _value: &T, _weight: f64) {}
+}
+
+impl <T, O:DistributionObserver<T> + ?Sized> DistributionObserver<T> for Arc<O> {
+    fn on_pick(&self, value: &T) {
+        (**self).on_pick(value);
+    }
+
+    fn on_add(&self, value: &T, weight: f64) {
+        (**self).on_add(value, weight);
+    }
+
+    fn on_remove(&self, value: &T, weight: f64) {
+        (**self).on_remove(value, weight);
+    }
+}
+
+// Clones of a distribution share its observer.
+pub(crate) struct ObserverSlot<T>(pub(crate) Option<Arc<dyn DistributionObserver<T>>>);
+
+impl <T> Clone for ObserverSlot<T> {
+    fn clone(&self) -> Self {
+        ObserverSlot(self.0.clone())
+    }
+}
+
+impl <T> Debug for ObserverSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {"Some(..)"} else {"None"})
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::{Arc, Mutex};
+    use crate::{Distribution, DistributionObserver};
+
+    // Records every callback as text; shared with the tests of other modules.
+    #[derive(Default)]
+    pub(crate) struct EventLog {
+        events: Mutex<Vec<String>>
+    }
+
+    impl EventLog {
+        pub(crate) fn events(&self) -> Vec<String> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl DistributionObserver<char> for EventLog {
+        fn on_pick(&self, value: &char) {
+            self.events.lock().unwrap().push(format!("pick {value}"));
+        }
+
+        fn on_add(&self, value: &char, weight: f64) {
+            self.events.lock().unwrap().push(format!("add {value} {weight}"));
+        }
+
+        fn on_remove(&self, value: &char, weight: f64) {
+            self.events.lock().unwrap().push(format!("remove {value} {weight}"));
+        }
+    }
+
+    #[test]
+    fn test_observer_callbacks() {
+        let log = Arc::new(EventLog::default());
+        let mut dist = Distribution::new();
+        dist.add(&'x', 1.0);
+        dist.set_observer(log.clone());
+        dist.add(&'a', 2.0);
+        assert_eq!(dist.remove(&'x'), Some(1.0));
+        assert_eq!(dist.remove(&'x'), None);
+        assert_eq!(dist.random_pick(), 'a');
+        dist.clear_observer();
+        dist.random_pick();
+        assert_eq!(*log.events.lock().unwrap(), vec!["add a 2", "remove x 1", "pick a"]);
+    }
+}
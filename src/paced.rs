@@ -0,0 +1,167 @@
+// Picks are scheduled on an absolute timeline: each pick's due time is the previous due time
+// plus a gap, so a consumer that falls behind catches up instead of drifting below the rate.
+
+use std::{fmt::Debug, thread, time::{Duration, Instant}};
+use rand::{Rng, RngCore};
+use rand_distr::Exp;
+use crate::Distribution;
+
+type GapFn = Box<dyn Fn(&mut dyn RngCore)->f64 + Send>;
+
+/// Yields picks from a distribution at a target rate, with the gaps between picks either fixed
+/// or drawn from an inter-arrival distribution giving seconds.
+pub struct PacedSampler<T> {
+    dist: Distribution<T>,
+    gap: GapFn,
+    due: Option<Instant>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> PacedSampler<T> {
+    /// Evenly spaced picks, `per_second` of them each second.
+    pub fn new(dist: Distribution<T>, per_second: f64) -> Self {
+        assert!(per_second.is_finite() && per_second > 0.0);
+        let interval = 1.0 / per_second;
+        Self::with_interarrival(dist, move |_: &mut dyn RngCore| interval)
+    }
+
+    /// Picks arriving as a Poisson process averaging `per_second` picks each second.
+    pub fn poisson(dist: Distribution<T>, per_second: f64) -> Self {
+        assert!(per_second.is_finite() && per_second > 0.0);
+        let gaps = Exp::new(per_second).unwrap();
+        Self::with_interarrival(dist, move |rng: &mut dyn RngCore| rand_distr::Distribution::sample(&gaps, rng))
+    }
+
+    /// Picks separated by `gap(rng)` seconds; negative gaps count as zero. A pick panics if the
+    /// gap that follows it is NaN, infinite, or too long to schedule.
+    pub fn with_interarrival<G:Fn(&mut dyn RngCore)->f64 + Send + 'static>(dist: Distribution<T>, gap: G) -> Self {
+        assert!(!dist.is_empty());
+        PacedSampler {dist, gap: Box::new(gap), due: None}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    /// When the next pick is due, or `None` before the first pick.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.due
+    }
+
+    /// Waits until the next pick is due, then makes it.
+    pub fn pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    /// As `pick()`, drawing both the gaps and the picks from `rng`.
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        loop {
+            let now = Instant::now();
+            match self.try_pick_at_with(now, rng) {
+                Some(picked) => return picked,
+                None => thread::sleep(self.due.unwrap() - now)
+            }
+        }
+    }
+
+    /// Makes a pick if one is due at `now`. The first pick is due on the first call.
+    pub fn try_pick_at(&mut self, now: Instant) -> Option<T> {
+        self.try_pick_at_with(now, &mut rand::thread_rng())
+    }
+
+    /// As `try_pick_at()`, drawing both the gap and the pick from `rng`, so a seeded generator
+    /// and the same sequence of times reproduce the schedule and the picks.
+    pub fn try_pick_at_with<R:Rng + ?Sized>(&mut self, now: Instant, mut rng: &mut R) -> Option<T> {
+        let due = *self.due.get_or_insert(now);
+        if now < due {
+            return None;
+        }
+        let gap = (self.gap)(&mut rng);
+        let next = if gap.is_nan() {None} else {
+            Duration::try_from_secs_f64(gap.max(0.0)).ok().and_then(|gap| due.checked_add(gap))
+        };
+        self.due = Some(next.unwrap_or_else(|| panic!("gap between picks must be finite and in range, got {gap}")));
+        Some(self.dist.notify_pick(self.dist.pick_ref(rng)).clone())
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Iterator for PacedSampler<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.pick())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use rand::RngCore;
+    use crate::{Distribution, PacedSampler, SplitMix64};
+
+    fn example() -> Distribution<char> {
+        vec![('a', 1.0), ('b', 3.0)].into()
+    }
+
+    #[test]
+    fn test_fixed_rate() {
+        let mut paced = PacedSampler::new(example(), 10.0);
+        let start = Instant::now();
+        assert!(paced.try_pick_at(start).is_some());
+        assert!(paced.try_pick_at(start + Duration::from_millis(50)).is_none());
+        assert!(paced.try_pick_at(start + Duration::from_millis(100)).is_some());
+        assert!(paced.try_pick_at(start + Duration::from_millis(350)).is_some());
+        assert_eq!(paced.next_due(), Some(start + Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn test_poisson_rate() {
+        let mut paced = PacedSampler::poisson(example(), 100.0);
+        let start = Instant::now();
+        let mut now = start;
+        for _ in 0..2000 {
+            assert!(paced.try_pick_at(now).is_some());
+            now = paced.next_due().unwrap();
+        }
+        let elapsed = (now - start).as_secs_f64();
+        assert!((16.0..24.0).contains(&elapsed));
+    }
+
+    #[test]
+    fn test_seeded_schedule() {
+        let start = Instant::now();
+        let run = |seed| {
+            let mut paced = PacedSampler::poisson(example(), 50.0);
+            let mut rng = SplitMix64::new(seed);
+            let mut now = start;
+            let mut picks = Vec::new();
+            for _ in 0..100 {
+                picks.push(paced.try_pick_at_with(now, &mut rng).unwrap());
+                now = paced.next_due().unwrap();
+            }
+            (picks, now)
+        };
+        assert_eq!(run(5), run(5));
+        assert_ne!(run(5).1, run(6).1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_poisson_rejects_zero_rate() {
+        PacedSampler::poisson(example(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "gap between picks must be finite")]
+    fn test_infinite_gap() {
+        let mut paced = PacedSampler::with_interarrival(example(), |_: &mut dyn RngCore| f64::INFINITY);
+        paced.try_pick_at(Instant::now());
+    }
+
+    #[test]
+    fn test_blocking_iterator() {
+        let start = Instant::now();
+        let picks: Vec<char> = PacedSampler::new(example(), 200.0).take(5).collect();
+        assert_eq!(picks.len(), 5);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,102 @@
+// Each segment between neighbouring knots is a trapezoid. Sampling picks a segment by area,
+// then inverts the segment's quadratic cumulative area.
+
+use rand::Rng;
+use crate::IndexDistribution;
+
+/// A continuous distribution whose density changes linearly between `(x, density)` knots and
+/// is zero outside them.
+#[derive(Clone, Debug)]
+pub struct PiecewiseLinear {
+    knots: Vec<(f64, f64)>,
+    segments: IndexDistribution
+}
+
+impl PiecewiseLinear {
+    /// The knots need strictly increasing x values and non-negative densities, which need not
+    /// be normalized.
+    pub fn new(knots: &[(f64, f64)]) -> Self {
+        assert!(knots.len() >= 2);
+        assert!(knots.iter().all(|(x, density)| x.is_finite() && density.is_finite() && *density >= 0.0));
+        assert!(knots.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        let areas: Vec<f64> = knots.windows(2).map(|pair| segment_area(pair[0], pair[1])).collect();
+        PiecewiseLinear {knots: knots.to_vec(), segments: IndexDistribution::new(&areas)}
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.segments.total_weight()
+    }
+
+    /// The normalized density at `x`.
+    pub fn density(&self, x: f64) -> f64 {
+        self.knots.windows(2)
+            .find(|pair| pair[0].0 <= x && x <= pair[1].0)
+            .map_or(0.0, |pair| {
+                let ((x0, d0), (x1, d1)) = (pair[0], pair[1]);
+                d0 + (d1 - d0) * (x - x0) / (x1 - x0)
+            }) / self.total_area()
+    }
+
+    /// The probability of sampling a value no greater than `x`.
+    pub fn cdf(&self, x: f64) -> f64 {
+        let mut area = 0.0;
+        for pair in self.knots.windows(2) {
+            let ((x0, d0), (x1, _)) = (pair[0], pair[1]);
+            if x >= x1 {
+                area += segment_area(pair[0], pair[1]);
+            } else if x > x0 {
+                area += segment_area(pair[0], (x, d0 + (pair[1].1 - d0) * (x - x0) / (x1 - x0)));
+            }
+        }
+        area / self.total_area()
+    }
+
+    pub fn sample(&self) -> f64 {
+        self.sample_with(&mut rand::thread_rng())
+    }
+
+    pub fn sample_with<R:Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let i = self.segments.pick_with(rng);
+        let ((x0, d0), (x1, d1)) = (self.knots[i], self.knots[i + 1]);
+        let target = rng.gen::<f64>() * segment_area(self.knots[i], self.knots[i + 1]);
+        if target == 0.0 {
+            return x0;
+        }
+        // Solves d0 * t + slope * t^2 / 2 = target in a form that stays exact for a flat segment.
+        let slope = (d1 - d0) / (x1 - x0);
+        let t = 2.0 * target / (d0 + (d0 * d0 + 2.0 * slope * target).max(0.0).sqrt());
+        (x0 + t).min(x1)
+    }
+}
+
+fn segment_area((x0, d0): (f64, f64), (x1, d1): (f64, f64)) -> f64 {
+    (x1 - x0) * (d0 + d1) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PiecewiseLinear, SplitMix64};
+
+    #[test]
+    fn test_density_and_cdf() {
+        let triangle = PiecewiseLinear::new(&[(0.0, 0.0), (1.0, 2.0), (2.0, 0.0)]);
+        assert_eq!(triangle.total_area(), 2.0);
+        assert_eq!(triangle.density(1.0), 1.0);
+        assert_eq!(triangle.density(5.0), 0.0);
+        assert_eq!(triangle.cdf(1.0), 0.5);
+        assert_eq!(triangle.cdf(0.5), 0.125);
+        assert_eq!(triangle.cdf(3.0), 1.0);
+    }
+
+    #[test]
+    fn test_sample_matches_cdf() {
+        let ramp = PiecewiseLinear::new(&[(10.0, 1.0), (20.0, 1.0), (30.0, 3.0)]);
+        let mut rng = SplitMix64::new(8);
+        let samples: Vec<f64> = (0..10_000).map(|_| ramp.sample_with(&mut rng)).collect();
+        assert!(samples.iter().all(|x| (10.0..=30.0).contains(x)));
+        for x in [12.0, 20.0, 25.0, 29.0] {
+            let below = samples.iter().filter(|s| **s <= x).count() as f64 / samples.len() as f64;
+            assert!((below - ramp.cdf(x)).abs() < 0.02);
+        }
+    }
+}
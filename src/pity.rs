@@ -0,0 +1,158 @@
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::Rng;
+use crate::Distribution;
+
+/// How a value's chance grows while it keeps being missed. Once more than `soft_start`
+/// consecutive draws have missed it, its weight is multiplied by `1 + soft_step * n` on the
+/// n-th draw past that point, and the `hard_limit`-th draw is guaranteed to produce it. Rules
+/// are built with `hard()` or `soft()`, which check that the parameters fit together.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PityRule {
+    soft_start: usize,
+    soft_step: f64,
+    hard_limit: usize
+}
+
+impl PityRule {
+    pub fn hard(hard_limit: usize) -> Self {
+        PityRule::soft(hard_limit, 0.0, hard_limit)
+    }
+
+    pub fn soft(soft_start: usize, soft_step: f64, hard_limit: usize) -> Self {
+        assert!(hard_limit > 0 && soft_start <= hard_limit && soft_step.is_finite() && soft_step >= 0.0);
+        PityRule {soft_start, soft_step, hard_limit}
+    }
+
+    pub fn soft_start(&self) -> usize {
+        self.soft_start
+    }
+
+    pub fn soft_step(&self) -> f64 {
+        self.soft_step
+    }
+
+    pub fn hard_limit(&self) -> usize {
+        self.hard_limit
+    }
+
+    fn multiplier(&self, misses: usize) -> f64 {
+        let draw = misses + 1;
+        if draw > self.soft_start {1.0 + self.soft_step * (draw - self.soft_start) as f64} else {1.0}
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PityDistribution<T> {
+    dist: Distribution<T>,
+    rules: BTreeMap<T, PityRule>,
+    misses: BTreeMap<T, usize>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> PityDistribution<T> {
+    pub fn new(dist: Distribution<T>) -> Self {
+        PityDistribution {dist, rules: BTreeMap::new(), misses: BTreeMap::new()}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    pub fn add_rule(&mut self, value: &T, rule: PityRule) {
+        assert!(self.dist.originals.contains_key(value));
+        self.rules.insert(value.clone(), rule);
+        self.misses.insert(value.clone(), 0);
+    }
+
+    /// Consecutive draws since `value` last came up, if it has a pity rule.
+    pub fn misses(&self, value: &T) -> Option<usize> {
+        self.misses.get(value).copied()
+    }
+
+    pub fn reset(&mut self) {
+        self.misses.values_mut().for_each(|m| *m = 0);
+    }
+
+    pub fn pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        let picked = self.forced().cloned().unwrap_or_else(|| {
+            let boosted = self.boosted();
+            if boosted.is_empty() {
+                self.dist.notify_pick(self.dist.pick_ref(rng)).clone()
+            } else {
+                self.dist.with_overrides(boosted).pick_with(rng)
+            }
+        });
+
+        for (value, misses) in self.misses.iter_mut() {
+            *misses = if *value == picked {0} else {*misses + 1};
+        }
+        picked
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> PityDistribution<T> {
+    /// The weight `value` has on the next pick, with any boost applied. `None` for values that
+    /// cannot come up, including every value but the forced one when a hard limit is reached.
+    pub fn weight(&self, value: &T) -> Option<f64> {
+        match self.forced() {
+            Some(forced) => (forced == value).then(|| self.dist.originals[forced]),
+            None => self.boosted().get(value).copied().or_else(|| self.dist.get(value))
+        }
+    }
+
+    // The value whose hard limit the next pick reaches, preferring the longest drought.
+    fn forced(&self) -> Option<&T> {
+        self.rules.iter()
+            .filter(|(value, rule)| self.misses[*value] + 1 >= rule.hard_limit)
+            .max_by(|a, b| self.misses[a.0].cmp(&self.misses[b.0]).then_with(|| b.0.cmp(a.0)))
+            .map(|(value, _)| value)
+    }
+
+    fn boosted(&self) -> BTreeMap<T, f64> {
+        self.rules.iter()
+            .map(|(value, rule)| (value, rule.multiplier(self.misses[value])))
+            .filter(|(_, multiplier)| *multiplier > 1.0)
+            .map(|(value, multiplier)| (value.clone(), self.dist.originals[value] * multiplier))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, PityDistribution, PityRule};
+
+    fn gacha() -> PityDistribution<&'static str> {
+        let mut dist = Distribution::new();
+        dist.add(&"common", 1000.0);
+        dist.add(&"legendary", 0.001);
+        PityDistribution::new(dist)
+    }
+
+    #[test]
+    fn test_hard_pity() {
+        let mut pity = gacha();
+        pity.add_rule(&"legendary", PityRule::hard(10));
+        let picks: Vec<&str> = (0..50).map(|_| pity.pick()).collect();
+        for window in picks.windows(10) {
+            assert!(window.contains(&"legendary"));
+        }
+        assert!(pity.misses(&"legendary").unwrap() < 10);
+    }
+
+    #[test]
+    fn test_soft_pity_multiplier() {
+        let rule = PityRule::soft(5, 2.0, 20);
+        assert_eq!(rule.multiplier(3), 1.0);
+        assert_eq!(rule.multiplier(4), 1.0);
+        assert_eq!(rule.multiplier(5), 3.0);
+        assert_eq!(rule.multiplier(7), 7.0);
+
+        let mut pity = gacha();
+        pity.add_rule(&"legendary", PityRule::soft(1, 1e12, 100));
+        assert_eq!(pity.pick(), "common");
+        assert_eq!(pity.pick(), "legendary");
+    }
+}
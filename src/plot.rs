@@ -0,0 +1,77 @@
+// Bar charts drawn onto any plotters drawing area, one segment per value in ascending order.
+
+use std::{fmt::Debug, collections::BTreeMap};
+use plotters::{coord::Shift, prelude::*};
+use crate::Distribution;
+
+type PlotResult<DB> = Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Draws each value's probability as a filled bar.
+    pub fn plot_bar_chart<DB:DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> PlotResult<DB> {
+        self.plot(area, None)
+    }
+
+    /// Like `plot_bar_chart()`, with the empirical frequency of each value in `observed`
+    /// outlined over its expected bar.
+    pub fn plot_comparison<DB:DrawingBackend>(&self, observed: &BTreeMap<T, usize>, area: &DrawingArea<DB, Shift>) -> PlotResult<DB> {
+        self.plot(area, Some(observed))
+    }
+
+    fn plot<DB:DrawingBackend>(&self, area: &DrawingArea<DB, Shift>, observed: Option<&BTreeMap<T, usize>>) -> PlotResult<DB> {
+        let labels: Vec<String> = self.originals.keys().map(|value| format!("{value:?}")).collect();
+        let expected: Vec<f64> = self.probabilities().map(|(_, p)| p).collect();
+        let empirical: Option<Vec<f64>> = observed.map(|observed| {
+            let total = observed.values().sum::<usize>().max(1) as f64;
+            self.originals.keys().map(|value| observed.get(value).copied().unwrap_or(0) as f64 / total).collect()
+        });
+        let top = expected.iter().chain(empirical.iter().flatten()).copied().fold(0.0, f64::max).max(f64::MIN_POSITIVE);
+
+        let mut chart = ChartBuilder::on(area)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d((0..labels.len()).into_segmented(), 0.0..top * 1.1)?;
+        chart.configure_mesh()
+            .disable_x_mesh()
+            .y_desc("probability")
+            .x_label_formatter(&|x| match x {
+                SegmentValue::CenterOf(i) | SegmentValue::Exact(i) => labels.get(*i).cloned().unwrap_or_default(),
+                SegmentValue::Last => String::new()
+            })
+            .draw()?;
+        chart.draw_series(expected.iter().enumerate().map(|(i, p)| bar(i, *p, BLUE.mix(0.6).filled())))?;
+        if let Some(empirical) = empirical {
+            chart.draw_series(empirical.iter().enumerate().map(|(i, p)| bar(i, *p, RED.stroke_width(2))))?;
+        }
+        area.present()
+    }
+}
+
+fn bar(i: usize, height: f64, style: ShapeStyle) -> Rectangle<(SegmentValue<usize>, f64)> {
+    let mut rectangle = Rectangle::new([(SegmentValue::Exact(i), 0.0), (SegmentValue::Exact(i + 1), height)], style);
+    rectangle.set_margin(0, 0, 5, 5);
+    rectangle
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use plotters::prelude::*;
+    use crate::Distribution;
+
+    #[test]
+    fn test_plots_to_svg() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 3.0);
+        let mut svg = String::new();
+        dist.plot_bar_chart(&SVGBackend::with_string(&mut svg, (320, 240)).into_drawing_area()).unwrap();
+        assert!(svg.starts_with("<svg") && svg.matches("<rect").count() >= 2);
+
+        let observed: BTreeMap<char, usize> = [('a', 30), ('b', 70)].into_iter().collect();
+        let mut compared = String::new();
+        dist.plot_comparison(&observed, &SVGBackend::with_string(&mut compared, (320, 240)).into_drawing_area()).unwrap();
+        assert!(compared.matches("<rect").count() > svg.matches("<rect").count());
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::Debug;
+use polars::prelude::*;
+use crate::Distribution;
+
+/// Values that can be read from and written to a polars column.
+pub trait FrameValue: Sized {
+    /// Reads every row of `column`, casting it if needed; rows that are null or fail to cast are `None`.
+    fn from_column(column: &Column) -> PolarsResult<Vec<Option<Self>>>;
+
+    fn to_column(name: &str, values: Vec<Self>) -> Column;
+}
+
+macro_rules! frame_value_for_primitives {
+    ($($t:ty => $dtype:expr, $accessor:ident);*) => {
+        $(impl FrameValue for $t {
+            fn from_column(column: &Column) -> PolarsResult<Vec<Option<Self>>> {
+                Ok(column.cast(&$dtype)?.$accessor()?.iter().collect())
+            }
+
+            fn to_column(name: &str, values: Vec<Self>) -> Column {
+                Column::new(name.into(), values)
+            }
+        })*
+    }
+}
+
+frame_value_for_primitives!(i32 => DataType::Int32, i32; i64 => DataType::Int64, i64;
+                            u32 => DataType::UInt32, u32; u64 => DataType::UInt64, u64;
+                            bool => DataType::Boolean, bool);
+
+impl FrameValue for String {
+    fn from_column(column: &Column) -> PolarsResult<Vec<Option<Self>>> {
+        Ok(column.cast(&DataType::String)?.str()?.iter().map(|s| s.map(str::to_owned)).collect())
+    }
+
+    fn to_column(name: &str, values: Vec<Self>) -> Column {
+        Column::new(name.into(), values)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + FrameValue> Distribution<T> {
+    /// Builds a distribution from two columns of `df`. Fails on a null or uncastable cell and
+    /// on a weight that is not positive and finite; a repeated value keeps its last weight,
+    /// as with `add()`.
+    pub fn from_dataframe(df: &DataFrame, value_col: &str, weight_col: &str) -> PolarsResult<Self> {
+        let values = T::from_column(df.column(value_col)?)?;
+        let weights: Vec<Option<f64>> = df.column(weight_col)?.cast(&DataType::Float64)?.f64()?.iter().collect();
+        let mut result = Distribution::new();
+        for (row, (value, weight)) in values.into_iter().zip(weights).enumerate() {
+            match (value, weight) {
+                (Some(value), Some(weight)) if weight.is_finite() && weight > 0.0 => result.add(&value, weight),
+                (None, _) => return Err(PolarsError::ComputeError(format!("row {row}: missing value in {value_col:?}").into())),
+                (_, weight) => return Err(PolarsError::ComputeError(format!("row {row}: invalid weight {weight:?} in {weight_col:?}").into()))
+            }
+        }
+        result.trace_constructed("dataframe");
+        Ok(result)
+    }
+
+    /// A frame with a `value` and a `weight` column, one row per value in ascending order.
+    pub fn to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let values = T::to_column("value", self.originals.keys().cloned().collect());
+        let weights = Column::new("weight".into(), self.originals.values().copied().collect::<Vec<f64>>());
+        DataFrame::new_infer_height(vec![values, weights])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+    use crate::Distribution;
+
+    #[test]
+    fn test_dataframe_round_trip() {
+        let df = DataFrame::new_infer_height(vec![
+            Column::new("item".into(), vec!["b", "a"]),
+            Column::new("score".into(), vec![3i64, 1])
+        ]).unwrap();
+        let dist = Distribution::<String>::from_dataframe(&df, "item", "score").unwrap();
+        assert_eq!(dist.total_weight, 4.0);
+        assert_eq!(dist.get(&"b".to_owned()), Some(3.0));
+
+        let exported = dist.to_dataframe().unwrap();
+        assert_eq!(exported.height(), 2);
+        let restored = Distribution::<String>::from_dataframe(&exported, "value", "weight").unwrap();
+        assert_eq!(restored.originals, dist.originals);
+    }
+
+    #[test]
+    fn test_dataframe_rejects_bad_rows() {
+        let df = DataFrame::new_infer_height(vec![
+            Column::new("value".into(), vec![1i64, 2]),
+            Column::new("weight".into(), vec![Some(1.0), None])
+        ]).unwrap();
+        assert!(Distribution::<i64>::from_dataframe(&df, "value", "weight").is_err());
+        assert!(Distribution::<i64>::from_dataframe(&df, "missing", "weight").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt::Debug;
+use quickcheck::{Arbitrary, Gen};
+use crate::Distribution;
+
+// Weights are multiples of 0.001 up to 1000, so generated totals stay small and exact-ish.
+fn arbitrary_weight(g: &mut Gen) -> f64 {
+    (u32::arbitrary(g) % 1_000_000 + 1) as f64 / 1000.0
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Arbitrary> Arbitrary for Distribution<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let len = 1 + usize::arbitrary(g) % g.size().max(1);
+        let mut result = Distribution::new();
+        for _ in 0..len {
+            result.add(&T::arbitrary(g), arbitrary_weight(g));
+        }
+        result
+    }
+
+    /// Shrinks by removing one entry at a time, then by simplifying one weight at a time to 1.0
+    /// or its nearest integer.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let mut candidates = Vec::new();
+        if self.originals.len() > 1 {
+            for value in self.originals.keys() {
+                candidates.push(self.distro_with(|v| v != value));
+            }
+        }
+        for (value, weight) in self.originals.iter() {
+            for simpler in [1.0, weight.round()] {
+                if simpler >= 1.0 && simpler != *weight {
+                    let mut candidate = self.clone();
+                    candidate.add(value, simpler);
+                    candidates.push(candidate);
+                }
+            }
+        }
+        Box::new(candidates.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{quickcheck, Arbitrary};
+    use crate::Distribution;
+
+    quickcheck! {
+        fn generated_distributions_are_valid(dist: Distribution<u8>) -> bool {
+            !dist.is_empty() && dist.originals.values().all(|w| *w > 0.0 && w.is_finite())
+        }
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut dist = Distribution::new();
+        dist.add(&1u8, 2.5);
+        dist.add(&2u8, 1.0);
+        let shrunk: Vec<Distribution<u8>> = dist.shrink().collect();
+        assert_eq!(shrunk.len(), 4);
+        assert!(shrunk.iter().all(|d| !d.is_empty()));
+        assert_eq!(shrunk[0].originals.len(), 1);
+        assert_eq!(shrunk[2].originals[&1], 1.0);
+        assert_eq!(shrunk[3].originals[&1], 3.0);
+    }
+}
@@ -0,0 +1,98 @@
+use std::ops::Range;
+use rand::Rng;
+use crate::Distribution;
+
+/// Picks a range by weight, then a uniformly random integer within it, so large ranges never
+/// have their integers stored. Overlapping ranges are allowed; their weights add up.
+#[derive(Clone, Debug)]
+pub struct RangeDistribution {
+    ranges: Vec<Range<u64>>,
+    weights: Distribution<usize>
+}
+
+impl RangeDistribution {
+    pub fn new() -> Self {
+        RangeDistribution {ranges: Vec::new(), weights: Distribution::new()}
+    }
+
+    /// `weight` is the chance of the whole range, not of each integer in it.
+    pub fn add(&mut self, range: Range<u64>, weight: f64) {
+        assert!(!range.is_empty());
+        self.weights.add(&self.ranges.len(), weight);
+        self.ranges.push(range);
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn random_pick(&self) -> u64 {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let range = &self.ranges[*self.weights.pick_ref(rng)];
+        rng.gen_range(range.clone())
+    }
+
+    /// The weight of the single integer `x`: each range holding it contributes its weight
+    /// divided by its length.
+    pub fn weight(&self, x: u64) -> Option<f64> {
+        let weight: f64 = self.ranges.iter().enumerate()
+            .filter(|(_, range)| range.contains(&x))
+            .map(|(i, range)| self.weights.originals[&i] / (range.end - range.start) as f64)
+            .sum();
+        (weight > 0.0).then_some(weight)
+    }
+
+    /// The number of distinct integers covered by the ranges, counting overlaps once.
+    pub fn integer_count(&self) -> u64 {
+        let mut ranges = self.ranges.clone();
+        ranges.sort_by_key(|range| range.start);
+        let (mut count, mut covered_to) = (0, 0);
+        for range in ranges {
+            let start = range.start.max(covered_to);
+            if range.end > start {
+                count += range.end - start;
+                covered_to = range.end;
+            }
+        }
+        count
+    }
+}
+
+impl Default for RangeDistribution {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<(Range<u64>, f64)> for RangeDistribution {
+    fn from_iter<I:IntoIterator<Item=(Range<u64>, f64)>>(iter: I) -> Self {
+        let mut result = RangeDistribution::new();
+        for (range, weight) in iter {
+            result.add(range, weight);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RangeDistribution, SplitMix64};
+
+    #[test]
+    fn test_range_distribution() {
+        let ports: RangeDistribution = [(0..1024, 1.0), (49152..65536, 3.0), (u64::MAX - 1..u64::MAX, 0.001)].into_iter().collect();
+        assert_eq!(ports.len(), 3);
+        let mut rng = SplitMix64::new(11);
+        let picks: Vec<u64> = (0..4000).map(|_| ports.pick_with(&mut rng)).collect();
+        assert!(picks.iter().all(|p| *p < 1024 || (49152..65536).contains(p) || *p == u64::MAX - 1));
+        let high = picks.iter().filter(|p| (49152..65536).contains(*p)).count();
+        assert!((2850..3150).contains(&high));
+    }
+}
@@ -0,0 +1,121 @@
+// Views of a distribution ordered by weight instead of by value.
+
+use std::fmt::Debug;
+use rand::Rng;
+use crate::Distribution;
+
+/// Which value `weighted_median()` returns when the values up to some value hold exactly half
+/// of the weight, so that it and the next value are both medians.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MedianPolicy {
+    Lower,
+    Upper,
+    /// Picks one of the two at random with equal chances, from the generator given to
+    /// `weighted_median_with()`.
+    Either
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// The `k` heaviest values with their weights, heaviest first. Equal weights are listed in
+    /// ascending value order.
+    pub fn most_likely_k(&self, k: usize) -> Vec<(&T, f64)> {
+        let mut entries: Vec<(&T, f64)> = self.originals.iter().map(|(value, weight)| (value, *weight)).collect();
+        entries.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries.truncate(k);
+        entries
+    }
+
+    /// Every value, heaviest first, in the same order as `HashHistogram::ranking()` gives for counts.
+    pub fn ranking(&self) -> Vec<T> {
+        self.most_likely_k(self.originals.len()).into_iter().map(|(value, _)| value.clone()).collect()
+    }
+
+    /// The least value whose cumulative probability, in ascending value order, reaches one half,
+    /// with `policy` deciding exact ties. Returns `None` for an empty distribution.
+    pub fn weighted_median(&self, policy: MedianPolicy) -> Option<&T> {
+        self.weighted_median_with(policy, &mut rand::thread_rng())
+    }
+
+    /// As `weighted_median()`, with `MedianPolicy::Either` drawing from `rng`, which is left
+    /// untouched under the other policies or when there is no tie.
+    pub fn weighted_median_with<R:Rng + ?Sized>(&self, policy: MedianPolicy, rng: &mut R) -> Option<&T> {
+        let (lower, upper) = self.weighted_median_candidates()?;
+        let take_upper = match policy {
+            MedianPolicy::Lower => false,
+            MedianPolicy::Upper => true,
+            MedianPolicy::Either => lower != upper && rng.gen()
+        };
+        Some(if take_upper {upper} else {lower})
+    }
+
+    /// The lower and upper weighted medians, which are the same value unless the values up to
+    /// the lower one hold exactly half of the weight.
+    pub fn weighted_median_candidates(&self) -> Option<(&T, &T)> {
+        let mut cumulative = 0.0;
+        let mut entries = self.originals.iter();
+        while let Some((value, weight)) = entries.next() {
+            cumulative += weight;
+            if 2.0 * cumulative > self.total_weight {
+                return Some((value, value));
+            }
+            if 2.0 * cumulative == self.total_weight {
+                return Some((value, entries.next().map_or(value, |(upper, _)| upper)));
+            }
+        }
+        self.originals.keys().next_back().map(|last| (last, last))
+    }
+
+    /// The `k` lightest values with their weights, lightest first. Equal weights are listed in
+    /// ascending value order.
+    pub fn least_likely_k(&self, k: usize) -> Vec<(&T, f64)> {
+        let mut entries: Vec<(&T, f64)> = self.originals.iter().map(|(value, weight)| (value, *weight)).collect();
+        entries.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+        entries.truncate(k);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, MedianPolicy, SplitMix64};
+
+    fn example() -> Distribution<char> {
+        vec![('a', 1.0), ('b', 5.0), ('c', 2.0), ('d', 5.0), ('e', 1.0)].into()
+    }
+
+    #[test]
+    fn test_most_likely_k() {
+        let dist = example();
+        assert_eq!(dist.most_likely_k(3), vec![(&'b', 5.0), (&'d', 5.0), (&'c', 2.0)]);
+        assert_eq!(dist.most_likely_k(10).len(), 5);
+        assert!(dist.most_likely_k(0).is_empty());
+    }
+
+    #[test]
+    fn test_least_likely_k() {
+        let dist = example();
+        assert_eq!(dist.least_likely_k(3), vec![(&'a', 1.0), (&'e', 1.0), (&'c', 2.0)]);
+        assert_eq!(dist.least_likely_k(5).last(), Some(&(&'d', 5.0)));
+    }
+
+    #[test]
+    fn test_ranking() {
+        assert_eq!(example().ranking(), vec!['b', 'd', 'c', 'a', 'e']);
+    }
+
+    #[test]
+    fn test_weighted_median() {
+        let dist = example();
+        assert_eq!(dist.weighted_median(MedianPolicy::Lower), Some(&'c'));
+        let tied: Distribution<u32> = vec![(1, 2.0), (2, 1.0), (3, 3.0)].into();
+        assert_eq!(tied.weighted_median(MedianPolicy::Lower), Some(&2));
+        assert_eq!(tied.weighted_median(MedianPolicy::Upper), Some(&3));
+        assert_eq!(tied.weighted_median_candidates(), Some((&2, &3)));
+        assert_eq!(dist.weighted_median_candidates(), Some((&'c', &'c')));
+        let mut rng = SplitMix64::new(14);
+        let either: Vec<u32> = (0..100).map(|_| *tied.weighted_median_with(MedianPolicy::Either, &mut rng).unwrap()).collect();
+        assert!(either.contains(&2) && either.contains(&3));
+        assert_eq!(dist.weighted_median_with(MedianPolicy::Either, &mut rng), Some(&'c'));
+        assert_eq!(Distribution::<u32>::new().weighted_median(MedianPolicy::Upper), None);
+    }
+}
@@ -0,0 +1,150 @@
+// Samplers that remember their recent picks and make them less likely to come up again.
+
+use std::{fmt::Debug, collections::{BTreeMap, VecDeque}};
+use rand::Rng;
+use crate::{views::pick_filtered, Distribution};
+
+/// Never picks the same value twice in a row, unless it is the only value.
+#[derive(Clone, Debug)]
+pub struct NonRepeatingSampler<T> {
+    dist: Distribution<T>,
+    last: Option<T>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> NonRepeatingSampler<T> {
+    pub fn new(dist: Distribution<T>) -> Self {
+        NonRepeatingSampler {dist, last: None}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.last.as_ref()
+    }
+
+    pub fn pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        let picked = pick_filtered(&self.dist, rng, |value| self.last.as_ref() != Some(value))
+            .unwrap_or_else(|| self.dist.notify_pick(self.dist.pick_ref(rng)))
+            .clone();
+        self.last = Some(picked.clone());
+        picked
+    }
+}
+
+/// Multiplies the weight of each of the last `window` picks by a penalty that starts at
+/// `penalty` for the most recent pick and rises linearly back to one as the pick ages out of
+/// the window. A value picked several times in the window is penalized once per pick.
+#[derive(Clone, Debug)]
+pub struct RecencySampler<T> {
+    dist: Distribution<T>,
+    window: usize,
+    penalty: f64,
+    recent: VecDeque<T>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> RecencySampler<T> {
+    pub fn new(dist: Distribution<T>, window: usize, penalty: f64) -> Self {
+        assert!(penalty > 0.0 && penalty <= 1.0);
+        RecencySampler {dist, window, penalty, recent: VecDeque::with_capacity(window)}
+    }
+
+    pub fn distribution(&self) -> &Distribution<T> {
+        &self.dist
+    }
+
+    /// The current multiplier of `value`'s weight.
+    pub fn multiplier(&self, value: &T) -> f64 {
+        self.recent.iter().enumerate()
+            .filter(|(_, recent)| *recent == value)
+            .map(|(age, _)| self.penalty_at(age))
+            .product()
+    }
+
+    pub fn pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        let mut penalized: BTreeMap<T, f64> = BTreeMap::new();
+        for (age, value) in self.recent.iter().enumerate() {
+            *penalized.entry(value.clone()).or_insert(self.dist.originals[value]) *= self.penalty_at(age);
+        }
+        let picked = self.dist.with_overrides(penalized).pick_with(rng);
+        if self.window > 0 {
+            if self.recent.len() == self.window {
+                self.recent.pop_back();
+            }
+            self.recent.push_front(picked.clone());
+        }
+        picked
+    }
+
+    pub fn reset(&mut self) {
+        self.recent.clear();
+    }
+
+    fn penalty_at(&self, age: usize) -> f64 {
+        1.0 - (1.0 - self.penalty) * (self.window - age) as f64 / self.window as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, NonRepeatingSampler, RecencySampler, SplitMix64};
+
+    #[test]
+    fn test_no_immediate_repeats() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1000.0);
+        dist.add(&'b', 1.0);
+        dist.add(&'c', 1.0);
+        let mut sampler = NonRepeatingSampler::new(dist);
+        let picks: Vec<char> = (0..500).map(|_| sampler.pick()).collect();
+        assert!(picks.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(picks.iter().filter(|c| **c == 'a').count() > 200);
+        assert_eq!(sampler.last(), picks.last());
+
+        let mut single = Distribution::new();
+        single.add(&'z', 1.0);
+        let mut sampler = NonRepeatingSampler::new(single);
+        assert_eq!(sampler.pick(), 'z');
+        assert_eq!(sampler.pick(), 'z');
+
+        let seeded = |seed| {
+            let mut sampler = NonRepeatingSampler::new(vec![('a', 1.0), ('b', 2.0), ('c', 3.0)].into());
+            let mut rng = SplitMix64::new(seed);
+            (0..30).map(|_| sampler.pick_with(&mut rng)).collect::<String>()
+        };
+        assert_eq!(seeded(1), seeded(1));
+    }
+
+    #[test]
+    fn test_recency_penalty() {
+        let mut dist = Distribution::new();
+        for c in 'a'..='d' {
+            dist.add(&c, 1.0);
+        }
+        let mut sampler = RecencySampler::new(dist, 2, 0.01);
+        let first = sampler.pick();
+        assert!((sampler.multiplier(&first) - 0.01).abs() < 1e-12);
+        let picks: Vec<char> = (0..400).map(|_| sampler.pick()).collect();
+        let repeats = picks.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        assert!(repeats < 20);
+        let last = *picks.last().unwrap();
+        sampler.reset();
+        assert_eq!(sampler.multiplier(&last), 1.0);
+
+        let mut seeded = |seed| {
+            sampler.reset();
+            let mut rng = SplitMix64::new(seed);
+            (0..30).map(|_| sampler.pick_with(&mut rng)).collect::<String>()
+        };
+        assert_eq!(seeded(1), seeded(1));
+    }
+}
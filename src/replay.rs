@@ -0,0 +1,116 @@
+use std::{fmt::Debug, time::SystemTime};
+use rand::Rng;
+use crate::Distribution;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickRecord<T> {
+    pub value: T,
+    /// The position drawn on `[0, total_weight)`.
+    pub draw: f64,
+    pub total_weight: f64,
+    pub timestamp: SystemTime
+}
+
+pub trait PickSink<T> {
+    fn record(&mut self, record: PickRecord<T>);
+}
+
+impl <T> PickSink<T> for Vec<PickRecord<T>> {
+    fn record(&mut self, record: PickRecord<T>) {
+        self.push(record);
+    }
+}
+
+impl <T> PickSink<T> for std::sync::mpsc::Sender<PickRecord<T>> {
+    fn record(&mut self, record: PickRecord<T>) {
+        let _ = self.send(record);
+    }
+}
+
+/// Picks from a distribution, logging every pick to a sink.
+pub struct Recorder<'a, T, S> {
+    dist: &'a Distribution<T>,
+    sink: S
+}
+
+impl <'a, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, S:PickSink<T>> Recorder<'a, T, S> {
+    pub fn random_pick(&mut self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> T {
+        assert!(!self.dist.is_empty());
+        let draw = rng.gen_range(0.0..self.dist.total_weight);
+        let value = self.dist.notify_pick(self.dist.value_at(draw).unwrap()).clone();
+        self.sink.record(PickRecord {value: value.clone(), draw, total_weight: self.dist.total_weight, timestamp: SystemTime::now()});
+        value
+    }
+
+    pub fn distribution(&self) -> &'a Distribution<T> {
+        self.dist
+    }
+
+        pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    pub fn recorder<S:PickSink<T>>(&self, sink: S) -> Recorder<'_, T, S> {
+        Recorder {dist: self, sink}
+    }
+
+    /// Re-issues the draws in `log` against this distribution. Against the table that produced
+    /// the log, this yields exactly the logged values; `None` marks a draw outside this table.
+    pub fn replay<'a>(&'a self, log: &'a [PickRecord<T>]) -> impl Iterator<Item=Option<&'a T>> {
+        log.iter().map(move |record| self.value_at(record.draw))
+    }
+
+    /// The index of the first logged pick that this distribution would not reproduce.
+    pub fn first_divergence(&self, log: &[PickRecord<T>]) -> Option<usize> {
+        self.replay(log).zip(log.iter()).position(|(replayed, record)| replayed != Some(&record.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, SplitMix64};
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut dist = Distribution::new();
+        dist.add(&"a", 1.0);
+        dist.add(&"b", 2.0);
+        dist.add(&"c", 3.0);
+
+        let mut recorder = dist.recorder(Vec::new());
+        let picked: Vec<&str> = (0..20).map(|_| recorder.random_pick()).collect();
+        let log = recorder.into_sink();
+        assert_eq!(log.len(), 20);
+        assert!(log.iter().all(|r| r.total_weight == 6.0));
+
+        let replayed: Vec<&str> = dist.replay(&log).map(|v| *v.unwrap()).collect();
+        assert_eq!(replayed, picked);
+        assert_eq!(dist.first_divergence(&log), None);
+
+        let mut changed = Distribution::new();
+        changed.add(&"c", 3.0);
+        changed.add(&"b", 2.0);
+        changed.add(&"a", 1.0);
+        assert_eq!(changed.first_divergence(&log), Some(0));
+
+        let seeded = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            let mut recorder = dist.recorder(Vec::new());
+            for _ in 0..10 {
+                recorder.pick_with(&mut rng);
+            }
+            recorder.into_sink().iter().map(|r| r.draw).collect::<Vec<_>>()
+        };
+        assert_eq!(seeded(3), seeded(3));
+    }
+}
@@ -0,0 +1,130 @@
+// A-ExpJ from Efraimidis and Spirakis, "Weighted random sampling with a reservoir" (2006).
+// Keys are kept in log space, ln(u) / weight, so that tiny weights do not underflow.
+
+use rand::{distributions::Open01, Rng};
+
+/// Keeps a weight-proportional sample of up to `capacity` items from a stream of unknown length.
+#[derive(Clone, Debug)]
+pub struct WeightedReservoir<T> {
+    capacity: usize,
+    values: Vec<T>,
+    keys: Vec<f64>,
+    min_index: usize,
+    skip: f64
+}
+
+impl <T> WeightedReservoir<T> {
+    pub fn new(capacity: usize) -> Self {
+        WeightedReservoir {capacity, values: Vec::with_capacity(capacity), keys: Vec::with_capacity(capacity), min_index: 0, skip: 0.0}
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn current(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+
+    pub fn push(&mut self, value: T, weight: f64) {
+        self.push_with(value, weight, &mut rand::thread_rng());
+    }
+
+    /// As `push()`, drawing the keys and skips from `rng`, so a seeded generator and the same
+    /// stream keep the same sample.
+    pub fn push_with<R:Rng + ?Sized>(&mut self, value: T, weight: f64, rng: &mut R) {
+        assert!(weight.is_finite() && weight > 0.0);
+        if self.capacity == 0 {
+            return;
+        }
+        if self.values.len() < self.capacity {
+            let u: f64 = rng.sample(Open01);
+            self.values.push(value);
+            self.keys.push(u.ln() / weight);
+            if self.values.len() == self.capacity {
+                self.update_threshold(rng);
+            }
+        } else {
+            self.skip -= weight;
+            if self.skip <= 0.0 {
+                let floor = (weight * self.keys[self.min_index]).exp().max(f64::MIN_POSITIVE);
+                let u = rng.gen_range(floor..1.0);
+                self.values[self.min_index] = value;
+                self.keys[self.min_index] = u.ln() / weight;
+                self.update_threshold(rng);
+            }
+        }
+    }
+
+    fn update_threshold<R:Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.min_index = (0..self.keys.len())
+            .min_by(|a, b| self.keys[*a].total_cmp(&self.keys[*b]))
+            .unwrap();
+        let u: f64 = rng.sample(Open01);
+        self.skip = u.ln() / self.keys[self.min_index];
+    }
+}
+
+impl <T> Extend<(T, f64)> for WeightedReservoir<T> {
+    fn extend<I: IntoIterator<Item = (T, f64)>>(&mut self, iter: I) {
+        for (value, weight) in iter {
+            self.push(value, weight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SplitMix64, WeightedReservoir};
+
+    #[test]
+    fn test_keeps_everything_below_capacity() {
+        let mut reservoir = WeightedReservoir::new(5);
+        reservoir.extend([("a", 1.0), ("b", 2.0), ("c", 3.0)]);
+        let mut kept = reservoir.current().to_vec();
+        kept.sort();
+        assert_eq!(kept, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_weighted_selection() {
+        let mut heavy_kept = 0;
+        for _ in 0..1000 {
+            let mut reservoir = WeightedReservoir::new(2);
+            reservoir.extend((0..50).map(|i| (i, 1.0)));
+            reservoir.push(-1, 1000.0);
+            reservoir.extend((50..100).map(|i| (i, 1.0)));
+            assert_eq!(reservoir.len(), 2);
+            if reservoir.current().contains(&-1) {
+                heavy_kept += 1;
+            }
+        }
+        assert!(heavy_kept > 950);
+    }
+
+    #[test]
+    fn test_seeded_sample() {
+        let sample = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            let mut reservoir = WeightedReservoir::new(3);
+            for i in 0..100 {
+                reservoir.push_with(i, 1.0 + i as f64, &mut rng);
+            }
+            reservoir.into_values()
+        };
+        assert_eq!(sample(11), sample(11));
+        assert_ne!(sample(11), sample(12));
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+use crate::Distribution;
+
+/// Smooth weighted round-robin, as used by nginx: every value appears in proportion to its
+/// weight, spread out as evenly as possible, with no randomness.
+#[derive(Clone, Debug)]
+pub struct WeightedRoundRobin<T> {
+    values: Vec<T>,
+    weights: Vec<f64>,
+    current: Vec<f64>,
+    total_weight: f64
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedRoundRobin<T> {
+    pub fn new(dist: &Distribution<T>) -> Self {
+        let (values, weights): (Vec<T>, Vec<f64>) = dist.originals.iter().map(|(v, w)| (v.clone(), *w)).unzip();
+        let current = vec![0.0; values.len()];
+        WeightedRoundRobin {values, weights, current, total_weight: dist.total_weight}
+    }
+}
+
+impl <T:Clone> Iterator for WeightedRoundRobin<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let mut best = 0;
+        for i in 0..self.values.len() {
+            self.current[i] += self.weights[i];
+            if self.current[i] > self.current[best] {
+                best = i;
+            }
+        }
+        self.current[best] -= self.total_weight;
+        Some(self.values[best].clone())
+    }
+}
+
+/// Deficit round-robin: each value earns its weight as quantum per round and is charged the cost
+/// of every item it serves, so service stays proportional to weight when item sizes differ.
+#[derive(Clone, Debug)]
+pub struct DeficitRoundRobin<T> {
+    values: Vec<T>,
+    quanta: Vec<f64>,
+    deficits: Vec<f64>,
+    position: usize
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> DeficitRoundRobin<T> {
+    pub fn new(dist: &Distribution<T>) -> Self {
+        let (values, quanta): (Vec<T>, Vec<f64>) = dist.originals.iter().map(|(v, w)| (v.clone(), *w)).unzip();
+        let mut deficits = vec![0.0; values.len()];
+        if let Some(first) = quanta.first() {
+            deficits[0] = *first;
+        }
+        DeficitRoundRobin {values, quanta, deficits, position: 0}
+    }
+
+    /// Picks the value that serves the next item, charging `cost` against its deficit.
+    pub fn next(&mut self, cost: f64) -> Option<T> {
+        assert!(cost >= 0.0);
+        if self.values.is_empty() {
+            return None;
+        }
+        while self.deficits[self.position] < cost {
+            self.position = (self.position + 1) % self.values.len();
+            self.deficits[self.position] += self.quanta[self.position];
+        }
+        self.deficits[self.position] -= cost;
+        Some(self.values[self.position].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DeficitRoundRobin, Distribution, WeightedRoundRobin};
+
+    #[test]
+    fn test_smooth_interleaving() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 5.0);
+        dist.add(&'b', 1.0);
+        dist.add(&'c', 1.0);
+        let order: String = WeightedRoundRobin::new(&dist).take(14).collect();
+        assert_eq!(order, "aabacaaaabacaa");
+        assert_eq!(WeightedRoundRobin::new(&Distribution::<char>::new()).next(), None);
+    }
+
+    #[test]
+    fn test_deficit_round_robin() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 3.0);
+        dist.add(&'b', 1.0);
+        let mut drr = DeficitRoundRobin::new(&dist);
+        let order: String = (0..8).map(|_| drr.next(1.0).unwrap()).collect();
+        assert_eq!(order, "aaabaaab");
+
+        let mut drr = DeficitRoundRobin::new(&dist);
+        let mut served = [0.0, 0.0];
+        for i in 0..400 {
+            let cost = if i % 2 == 0 {0.5} else {2.0};
+            match drr.next(cost).unwrap() {
+                'a' => served[0] += cost,
+                _ => served[1] += cost
+            }
+        }
+        assert!((served[0] / served[1] - 3.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,111 @@
+//! Parent selection for genetic algorithms. Each function takes the fitness of every member
+//! of a population, higher being fitter, and returns the indices of the selected members.
+
+use rand::Rng;
+use crate::Distribution;
+
+fn fitness_proportional(fitness: &[f64]) -> Distribution<usize> {
+    let mut dist = Distribution::new();
+    for (i, f) in fitness.iter().enumerate() {
+        assert!(*f >= 0.0);
+        if *f > 0.0 {
+            dist.add(&i, *f);
+        }
+    }
+    assert!(!dist.is_empty(), "no member has positive fitness");
+    dist
+}
+
+/// Picks `n` members independently in proportion to their non-negative fitness.
+pub fn roulette<R:Rng + ?Sized>(fitness: &[f64], n: usize, rng: &mut R) -> Vec<usize> {
+    let dist = fitness_proportional(fitness);
+    (0..n).map(|_| *dist.pick_ref(rng)).collect()
+}
+
+/// Stochastic universal sampling: like `roulette()`, but `n` evenly spaced pointers share a
+/// single random offset, so each member is selected within one of its expected count.
+pub fn stochastic_universal<R:Rng + ?Sized>(fitness: &[f64], n: usize, rng: &mut R) -> Vec<usize> {
+    let dist = fitness_proportional(fitness);
+    let offset: f64 = rng.gen();
+    (0..n).map(|i| *dist.value_at_fraction((offset + i as f64) / n as f64)).collect()
+}
+
+/// Picks `n` members in proportion to their rank, the least fit having rank 1, which keeps
+/// a few outliers from dominating. Fitness may be negative.
+pub fn rank<R:Rng + ?Sized>(fitness: &[f64], n: usize, rng: &mut R) -> Vec<usize> {
+    let mut ranks = Distribution::new();
+    for (position, i) in by_fitness(fitness).iter().rev().enumerate() {
+        ranks.add(i, (position + 1) as f64);
+    }
+    (0..n).map(|_| *ranks.pick_ref(rng)).collect()
+}
+
+/// Each of the `n` selections is the fittest of `size` members drawn uniformly with replacement.
+pub fn tournament<R:Rng + ?Sized>(fitness: &[f64], n: usize, size: usize, rng: &mut R) -> Vec<usize> {
+    assert!(size > 0 && !fitness.is_empty());
+    (0..n).map(|_| {
+        (0..size).map(|_| rng.gen_range(0..fitness.len()))
+            .reduce(|best, i| if fitness[i] > fitness[best] {i} else {best})
+            .unwrap()
+    }).collect()
+}
+
+/// The `elite` fittest members, followed by `n - elite` members picked as in `roulette()`.
+pub fn elitist<R:Rng + ?Sized>(fitness: &[f64], n: usize, elite: usize, rng: &mut R) -> Vec<usize> {
+    assert!(elite <= n && elite <= fitness.len());
+    let mut selected: Vec<usize> = by_fitness(fitness).into_iter().take(elite).collect();
+    if n > elite {
+        selected.extend(roulette(fitness, n - elite, rng));
+    }
+    selected
+}
+
+fn by_fitness(fitness: &[f64]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..fitness.len()).collect();
+    indices.sort_by(|a, b| fitness[*b].total_cmp(&fitness[*a]));
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{selection::*, SplitMix64};
+
+    const FITNESS: [f64; 4] = [1.0, 0.0, 3.0, 4.0];
+
+    #[test]
+    fn test_roulette_and_sus() {
+        let mut rng = SplitMix64::new(5);
+        let picks = roulette(&FITNESS, 8000, &mut rng);
+        assert!(!picks.contains(&1));
+        let threes = picks.iter().filter(|i| **i == 3).count();
+        assert!((3800..4200).contains(&threes));
+
+        let sus = stochastic_universal(&FITNESS, 8, &mut rng);
+        assert_eq!(sus.len(), 8);
+        assert_eq!(sus.iter().filter(|i| **i == 0).count(), 1);
+        assert_eq!(sus.iter().filter(|i| **i == 2).count(), 3);
+        assert_eq!(sus.iter().filter(|i| **i == 3).count(), 4);
+    }
+
+    #[test]
+    fn test_rank_and_tournament() {
+        let mut rng = SplitMix64::new(6);
+        let negative = [-5.0, -1.0, -100.0];
+        let picks = rank(&negative, 6000, &mut rng);
+        let best = picks.iter().filter(|i| **i == 1).count();
+        assert!((2800..3200).contains(&best));
+
+        assert!(tournament(&FITNESS, 50, 1, &mut rng).iter().any(|i| *i != 3));
+        let strong = tournament(&FITNESS, 100, 20, &mut rng);
+        assert!(strong.iter().filter(|i| **i == 3).count() > 95);
+    }
+
+    #[test]
+    fn test_elitist() {
+        let mut rng = SplitMix64::new(7);
+        let picks = elitist(&FITNESS, 5, 2, &mut rng);
+        assert_eq!(&picks[..2], &[3, 2]);
+        assert_eq!(picks.len(), 5);
+        assert_eq!(elitist(&FITNESS, 2, 2, &mut rng), vec![3, 2]);
+    }
+}
@@ -0,0 +1,121 @@
+// A worker thread owns the distribution and answers requests sent over a channel, so any
+// number of threads can pick from and update one table without sharing a lock. The worker
+// exits once every handle has been dropped.
+
+use std::{fmt::Debug, sync::mpsc::{self, Sender}, thread};
+use crate::Distribution;
+
+enum Request<T> {
+    Pick(Sender<Option<T>>),
+    PickN(usize, Sender<Vec<T>>),
+    Add(T, f64),
+    Remove(T, Sender<Option<f64>>),
+    Snapshot(Sender<Distribution<T>>)
+}
+
+/// A cloneable handle to a distribution served by a background thread.
+pub struct SamplerHandle<T> {
+    requests: Sender<Request<T>>
+}
+
+impl <T> Clone for SamplerHandle<T> {
+    fn clone(&self) -> Self {
+        SamplerHandle {requests: self.requests.clone()}
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Send + Sync + 'static> SamplerHandle<T> {
+    /// Moves `dist` onto a new worker thread and returns the first handle to it.
+    pub fn spawn(mut dist: Distribution<T>) -> Self {
+        let (requests, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            let mut rng = rand::thread_rng();
+            for request in incoming {
+                match request {
+                    Request::Pick(reply) => {
+                        let _ = reply.send((!dist.is_empty()).then(|| dist.notify_pick(dist.pick_ref(&mut rng)).clone()));
+                    }
+                    Request::PickN(n, reply) => {
+                        let picks = if dist.is_empty() {Vec::new()} else {
+                            (0..n).map(|_| dist.notify_pick(dist.pick_ref(&mut rng)).clone()).collect()
+                        };
+                        let _ = reply.send(picks);
+                    }
+                    Request::Add(value, weight) => dist.add(&value, weight),
+                    Request::Remove(value, reply) => {
+                        let _ = reply.send(dist.remove(&value));
+                    }
+                    Request::Snapshot(reply) => {
+                        let _ = reply.send(dist.clone());
+                    }
+                }
+            }
+        });
+        SamplerHandle {requests}
+    }
+
+    /// Returns `None` if the distribution is empty.
+    pub fn pick(&self) -> Option<T> {
+        self.ask(Request::Pick).flatten()
+    }
+
+    /// Returns `n` picks made in one round trip, or none if the distribution is empty.
+    pub fn pick_n(&self, n: usize) -> Vec<T> {
+        self.ask(|reply| Request::PickN(n, reply)).unwrap_or_default()
+    }
+
+    /// Queues the addition without waiting for it; later requests from this handle see it.
+    pub fn add(&self, value: T, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.requests.send(Request::Add(value, weight)).expect("sampler worker stopped");
+    }
+
+    pub fn remove(&self, value: T) -> Option<f64> {
+        self.ask(|reply| Request::Remove(value, reply)).flatten()
+    }
+
+    /// A copy of the distribution as it stands after every earlier request.
+    pub fn snapshot(&self) -> Distribution<T> {
+        self.ask(Request::Snapshot).expect("sampler worker stopped")
+    }
+
+    fn ask<A, F:FnOnce(Sender<A>)->Request<T>>(&self, request: F) -> Option<A> {
+        let (reply, answer) = mpsc::channel();
+        self.requests.send(request(reply)).ok()?;
+        answer.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use crate::{Distribution, SamplerHandle};
+
+    #[test]
+    fn test_picks_and_updates() {
+        let handle = SamplerHandle::spawn(vec![('a', 1.0)].into());
+        assert_eq!(handle.pick(), Some('a'));
+        handle.add('b', 1000.0);
+        assert!(handle.pick_n(50).iter().filter(|c| **c == 'b').count() > 40);
+        assert_eq!(handle.remove('b'), Some(1000.0));
+        assert_eq!(handle.remove('z'), None);
+        assert_eq!(handle.snapshot().keys().copied().collect::<String>(), "a");
+        assert_eq!(handle.remove('a'), Some(1.0));
+        assert_eq!(handle.pick(), None);
+        assert!(handle.pick_n(5).is_empty());
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let handle = SamplerHandle::spawn(Distribution::new());
+        let workers: Vec<_> = (0..4u32).map(|i| {
+            let handle = handle.clone();
+            thread::spawn(move || {
+                handle.add(i, 1.0);
+                handle.pick().is_some()
+            })
+        }).collect();
+        assert!(workers.into_iter().all(|worker| worker.join().unwrap()));
+        assert_eq!(handle.snapshot().keys().count(), 4);
+    }
+}
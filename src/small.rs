@@ -0,0 +1,156 @@
+use std::fmt::Debug;
+use rand::Rng;
+use crate::Distribution;
+
+pub const SMALL_CAPACITY: usize = 8;
+
+/// A distribution of at most `SMALL_CAPACITY` values stored inline, for A/B splits and short
+/// loot rolls. Nothing is allocated, and a pick is a linear scan over the weights in the order
+/// the values were added. It is opt-in: a `Distribution` keeps its own table however few
+/// values it holds, so only code that builds a `SmallDistribution` gains from it.
+#[derive(Clone, Debug)]
+pub struct SmallDistribution<T> {
+    entries: [Option<(T, f64)>; SMALL_CAPACITY],
+    len: usize,
+    total_weight: f64
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> SmallDistribution<T> {
+    pub fn new() -> Self {
+        SmallDistribution {entries: std::array::from_fn(|_| None), len: 0, total_weight: 0.0}
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == SMALL_CAPACITY
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    /// Adding a value that is already present replaces its weight. Panics if a new value would
+    /// exceed `SMALL_CAPACITY`.
+    pub fn add(&mut self, value: &T, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        if let Some(i) = self.position(value) {
+            let (_, old) = self.entries[i].as_mut().unwrap();
+            *old = weight;
+        } else {
+            assert!(!self.is_full(), "a SmallDistribution holds at most {SMALL_CAPACITY} values");
+            self.entries[self.len] = Some((value.clone(), weight));
+            self.len += 1;
+        }
+        self.total_weight = self.iter().map(|(_, weight)| weight).sum();
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<f64> {
+        let i = self.position(value)?;
+        let (_, weight) = self.entries[i].take().unwrap();
+        self.entries[i..self.len].rotate_left(1);
+        self.len -= 1;
+        self.total_weight = self.iter().map(|(_, weight)| weight).sum();
+        Some(weight)
+    }
+
+    pub fn get(&self, value: &T) -> Option<f64> {
+        self.iter().find(|(v, _)| *v == value).map(|(_, weight)| weight)
+    }
+
+    /// The values with their weights, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item=(&T, f64)> {
+        self.entries[..self.len].iter().map(|entry| {
+            let (value, weight) = entry.as_ref().unwrap();
+            (value, *weight)
+        })
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng()).clone()
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        assert!(!self.is_empty());
+        let mut target = rng.gen_range(0.0..self.total_weight);
+        let mut last = None;
+        for (value, weight) in self.iter() {
+            if target < weight {
+                return value;
+            }
+            target -= weight;
+            last = Some(value);
+        }
+        last.unwrap()
+    }
+
+    pub fn to_distribution(&self) -> Distribution<T> {
+        let mut result = Distribution::new();
+        for (value, weight) in self.iter() {
+            result.add(value, weight);
+        }
+        result
+    }
+
+    fn position(&self, value: &T) -> Option<usize> {
+        self.iter().position(|(v, _)| v == value)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for SmallDistribution<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SmallDistribution, SplitMix64, SMALL_CAPACITY};
+
+    #[test]
+    fn test_small_distribution() {
+        let mut split = SmallDistribution::new();
+        split.add(&"control", 1.0);
+        split.add(&"variant", 1.0);
+        split.add(&"control", 3.0);
+        assert_eq!(split.len(), 2);
+        assert_eq!(split.total_weight(), 4.0);
+        assert_eq!(split.get(&"control"), Some(3.0));
+
+        let mut rng = SplitMix64::new(21);
+        let control = (0..1000).filter(|_| *split.pick_with(&mut rng) == "control").count();
+        assert!((680..820).contains(&control));
+
+        assert_eq!(split.remove(&"control"), Some(3.0));
+        assert_eq!(split.remove(&"control"), None);
+        assert!((0..20).all(|_| split.random_pick() == "variant"));
+        assert_eq!(split.to_distribution().total_weight, 1.0);
+    }
+
+    #[test]
+    fn test_capacity_and_order() {
+        let mut dist = SmallDistribution::new();
+        for i in (0..SMALL_CAPACITY).rev() {
+            dist.add(&i, 1.0);
+        }
+        assert!(dist.is_full());
+        dist.remove(&5);
+        dist.add(&100, 2.0);
+        assert_eq!(dist.iter().map(|(v, _)| *v).collect::<Vec<_>>(), vec![7, 6, 4, 3, 2, 1, 0, 100]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rejects_overflow() {
+        let mut dist = SmallDistribution::new();
+        for i in 0..=SMALL_CAPACITY {
+            dist.add(&i, 1.0);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+// Scores become weights exp((score - max) / temperature). Subtracting the largest score keeps
+// the exponent at or below zero, so nothing overflows and the best value always has weight one.
+
+use std::fmt::Debug;
+use rand::Rng;
+use crate::{Distribution, IndexDistribution, Schedule};
+
+fn softmax_weights(scores: &[f64], temperature: f64) -> impl Iterator<Item = f64> + '_ {
+    assert!(temperature > 0.0 && scores.iter().all(|score| score.is_finite()));
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    scores.iter().map(move |score| ((score - max) / temperature).exp())
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Weights each value by the softmax of its score. Values whose weight underflows to zero are
+    /// left out, and a repeated value keeps its last score.
+    pub fn softmax<I:IntoIterator<Item=(T, f64)>>(scores: I, temperature: f64) -> Self {
+        let (values, scores): (Vec<T>, Vec<f64>) = scores.into_iter().unzip();
+        let mut result = Distribution::new();
+        for (value, weight) in values.iter().zip(softmax_weights(&scores, temperature)) {
+            if weight > 0.0 {
+                result.add(value, weight);
+            }
+        }
+        result.trace_constructed("softmax");
+        result
+    }
+}
+
+/// Keeps raw scores and samples their softmax at an adjustable temperature. Changing the
+/// temperature recomputes one flat vector of cumulative weights; no `Distribution` is rebuilt.
+#[derive(Clone, Debug)]
+pub struct SoftmaxSampler<T> {
+    values: Vec<T>,
+    scores: Vec<f64>,
+    temperature: f64,
+    weights: IndexDistribution,
+    step: u64
+}
+
+impl <T:Clone> SoftmaxSampler<T> {
+    pub fn new<I:IntoIterator<Item=(T, f64)>>(scores: I, temperature: f64) -> Self {
+        let (values, scores): (Vec<T>, Vec<f64>) = scores.into_iter().unzip();
+        let weights = IndexDistribution::new(&softmax_weights(&scores, temperature).collect::<Vec<_>>());
+        SoftmaxSampler {values, scores, temperature, weights, step: 0}
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    pub fn set_temperature(&mut self, temperature: f64) {
+        if temperature != self.temperature {
+            self.weights = IndexDistribution::new(&softmax_weights(&self.scores, temperature).collect::<Vec<_>>());
+            self.temperature = temperature;
+        }
+    }
+
+    pub fn pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.values[self.weights.pick_with(rng)].clone()
+    }
+
+    /// Each value with its weight at the current temperature, in the order they were given.
+    pub fn weights(&self) -> impl Iterator<Item=(&T, f64)> {
+        self.values.iter().enumerate().map(|(i, value)| (value, self.weights.weight(i).unwrap()))
+    }
+
+    /// Moves to the temperature `schedule` gives for the number of scheduled picks so far, then picks.
+    pub fn pick_scheduled<S:Schedule>(&mut self, schedule: &S) -> T {
+        self.pick_scheduled_with(schedule, &mut rand::thread_rng())
+    }
+
+    pub fn pick_scheduled_with<S:Schedule, R:Rng + ?Sized>(&mut self, schedule: &S, rng: &mut R) -> T {
+        self.set_temperature(schedule.temperature(self.step));
+        self.step += 1;
+        self.pick_with(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, ExponentialSchedule, SoftmaxSampler, SplitMix64};
+
+    #[test]
+    fn test_softmax() {
+        let dist = Distribution::softmax(vec![('a', 1000.0), ('b', 1000.0 + 2f64.ln()), ('c', -1e6)], 1.0);
+        assert_eq!(dist.originals.len(), 2);
+        assert_eq!(dist.get(&'b'), Some(1.0));
+        assert!((dist.get(&'a').unwrap() - 0.5).abs() < 1e-12);
+
+        let flat = Distribution::softmax(vec![('a', 1.0), ('b', 2.0)], 1e9);
+        assert!((flat.get(&'a').unwrap() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_softmax_sampler() {
+        let mut sampler = SoftmaxSampler::new(vec![("low", 0.0), ("high", 3.0)], 1000.0);
+        let mut rng = SplitMix64::new(4);
+        let high = (0..2000).filter(|_| sampler.pick_with(&mut rng) == "high").count();
+        assert!((900..1100).contains(&high));
+
+        sampler.set_temperature(0.01);
+        assert!((0..100).all(|_| sampler.pick_with(&mut rng) == "high"));
+
+        let schedule = ExponentialSchedule {initial: 1000.0, rate: 0.5};
+        sampler.pick_scheduled(&schedule);
+        assert_eq!(sampler.temperature(), 1000.0);
+        sampler.pick_scheduled(&schedule);
+        assert_eq!(sampler.temperature(), 500.0);
+
+        let mut replay = sampler.clone();
+        let (mut rng, mut replay_rng) = (SplitMix64::new(8), SplitMix64::new(8));
+        let picks: Vec<&str> = (0..20).map(|_| sampler.pick_scheduled_with(&schedule, &mut rng)).collect();
+        assert_eq!(picks, (0..20).map(|_| replay.pick_scheduled_with(&schedule, &mut replay_rng)).collect::<Vec<_>>());
+        assert_eq!(sampler.temperature(), 1000.0 * 0.5f64.powi(21));
+    }
+}
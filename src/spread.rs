@@ -0,0 +1,87 @@
+// The `len` slots are apportioned by largest remainder: each value gets the floor of its share
+// of `len`, and the slots left over go to the largest fractional parts. Each value's copies are
+// placed at evenly spaced positions in [0, 1) behind a random offset of its own. Every position
+// is jittered by up to a tenth of its spacing, and sorting all positions gives the order.
+
+use std::fmt::Debug;
+use rand::Rng;
+use crate::Distribution;
+
+const JITTER: f64 = 0.1;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Lists `len` values, each appearing in proportion to its probability, with the copies of
+    /// each value spread as evenly as possible through the list. A value whose share of `len`
+    /// is below one may not appear at all.
+    pub fn spread_order(&self, len: usize) -> Vec<T> {
+        self.spread_order_with(len, &mut rand::thread_rng())
+    }
+
+    pub fn spread_order_with<R:Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Vec<T> {
+        let mut positioned = Vec::with_capacity(len);
+        for (value, copies) in self.apportion(len) {
+            let spacing = 1.0 / copies as f64;
+            let offset = rng.gen_range(0.0..spacing);
+            for i in 0..copies {
+                let jitter = rng.gen_range(-JITTER..JITTER) * spacing;
+                positioned.push((offset + i as f64 * spacing + jitter, value));
+            }
+        }
+        positioned.sort_by(|a, b| a.0.total_cmp(&b.0));
+        positioned.into_iter().map(|(_, value)| value.clone()).collect()
+    }
+
+    fn apportion(&self, len: usize) -> Vec<(&T, usize)> {
+        let mut shares: Vec<(&T, usize, f64)> = self.originals.iter()
+            .map(|(value, weight)| {
+                let quota = weight / self.total_weight * len as f64;
+                (value, quota as usize, quota.fract())
+            })
+            .collect();
+        let left_over = len.saturating_sub(shares.iter().map(|(_, copies, _)| copies).sum());
+        let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+        by_remainder.sort_by(|a, b| shares[*b].2.total_cmp(&shares[*a].2));
+        for i in by_remainder.into_iter().take(left_over) {
+            shares[i].1 += 1;
+        }
+        shares.into_iter()
+            .filter(|(_, copies, _)| *copies > 0)
+            .map(|(value, copies, _)| (value, copies))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Distribution;
+
+    #[test]
+    fn test_spread_order() {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 4.0);
+        dist.add(&'b', 1.6);
+        dist.add(&'c', 0.2);
+        let order = dist.spread_order(29);
+        assert_eq!(order.len(), 29);
+        assert_eq!(order.iter().filter(|c| **c == 'a').count(), 20);
+        assert_eq!(order.iter().filter(|c| **c == 'b').count(), 8);
+        assert_eq!(order.iter().filter(|c| **c == 'c').count(), 1);
+
+        let a_positions: Vec<usize> = order.iter().enumerate().filter(|(_, c)| **c == 'a').map(|(i, _)| i).collect();
+        assert!(a_positions.windows(2).all(|pair| pair[1] - pair[0] <= 3));
+    }
+
+    #[test]
+    fn test_spread_order_ignores_weight_scale() {
+        let small: Distribution<char> = vec![('a', 1.0), ('b', 2.0), ('c', 3.0)].into();
+        let huge: Distribution<char> = vec![('a', 1e9), ('b', 2e9), ('c', 3e9)].into();
+        for len in [0, 1, 5, 10] {
+            let mut from_small = small.spread_order(len);
+            let mut from_huge = huge.spread_order(len);
+            assert_eq!(from_small.len(), len);
+            from_small.sort();
+            from_huge.sort();
+            assert_eq!(from_small, from_huge);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use rand::Rng;
+use crate::{pick_from_ends, weight_from_ends};
+
+/// A distribution held entirely in static slices, with its cumulative weights worked out ahead
+/// of time. The `static_distribution!` macro, available with the `macros` feature, builds one
+/// from `value => weight` pairs and checks the weights as the crate compiles.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticTable<T:'static> {
+    values: &'static [T],
+    // ends[i] is the total weight of values 0..=i.
+    ends: &'static [f64]
+}
+
+impl <T:'static> StaticTable<T> {
+    /// Pairs `values` with running totals of their weights, which must be as many as the values
+    /// and should be strictly increasing: a value whose total equals the one before it is never
+    /// picked. `static_distribution!` guarantees both, rejecting weights too small to raise the
+    /// running total.
+    pub const fn from_cumulative(values: &'static [T], ends: &'static [f64]) -> Self {
+        assert!(!values.is_empty() && values.len() == ends.len());
+        StaticTable {values, ends}
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.ends[self.ends.len() - 1]
+    }
+
+    pub fn values(&self) -> &'static [T] {
+        self.values
+    }
+
+    pub fn weight_at(&self, index: usize) -> Option<f64> {
+        weight_from_ends(self.ends, index)
+    }
+
+    pub fn random_pick(&self) -> &'static T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> &'static T {
+        &self.values[pick_from_ends(self.ends, rng)]
+    }
+}
+
+/// A distribution over a borrowed array of `(value, weight)` pairs that never allocates, so
+/// it can be declared as a `static` or `const` without the `macros` feature. `new()` checks the
+/// weights and works out their running totals, as the crate compiles when it initializes a
+/// `static` or `const`, and picks search those totals as `StaticTable` does. The price is that
+/// the type names the number of pairs; `static_distribution!` avoids that by building a
+/// `StaticTable`, whose slices can be any length.
+#[derive(Debug)]
+pub struct StaticDistribution<'a, T, const N: usize> {
+    pairs: &'a [(T, f64); N],
+    ends: [f64; N]
+}
+
+impl <'a, T, const N: usize> StaticDistribution<'a, T, N> {
+    /// Panics if there are no pairs or any weight is not positive and finite.
+    pub const fn new(pairs: &'a [(T, f64); N]) -> Self {
+        assert!(N > 0, "a static distribution needs at least one value");
+        let mut ends = [0.0; N];
+        let mut total = 0.0;
+        let mut i = 0;
+        while i < N {
+            let weight = pairs[i].1;
+            assert!(weight.is_finite() && weight > 0.0, "weights must be positive and finite");
+            total += weight;
+            ends[i] = total;
+            i += 1;
+        }
+        assert!(total.is_finite(), "the total weight overflows f64");
+        StaticDistribution {pairs, ends}
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub fn pairs(&self) -> &'a [(T, f64)] {
+        self.pairs
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.ends[N - 1]
+    }
+
+    pub fn random_pick(&self) -> &'a T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> &'a T {
+        &self.pairs[pick_from_ends(&self.ends, rng)].0
+    }
+}
+
+impl <T, const N: usize> Clone for StaticDistribution<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl <T, const N: usize> Copy for StaticDistribution<'_, T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SplitMix64, StaticDistribution, StaticTable};
+
+    static TABLE: StaticTable<char> = StaticTable::from_cumulative(&['a', 'b', 'c'], &[1.0, 1.5, 4.0]);
+
+    #[test]
+    fn test_static_table() {
+        assert_eq!(TABLE.len(), 3);
+        assert_eq!(TABLE.total_weight(), 4.0);
+        assert_eq!(TABLE.weight_at(1), Some(0.5));
+        assert_eq!(TABLE.weight_at(3), None);
+        let mut rng = SplitMix64::new(5);
+        let c_count = (0..400).filter(|_| *TABLE.pick_with(&mut rng) == 'c').count();
+        assert!((250..350).contains(&c_count));
+    }
+
+    static LOOT: StaticDistribution<&str, 3> = StaticDistribution::new(&[("copper", 6.0), ("silver", 3.0), ("gold", 1.0)]);
+    const COIN: StaticDistribution<bool, 2> = StaticDistribution::new(&[(false, 1.0), (true, 1.0)]);
+
+    #[test]
+    fn test_static_distribution() {
+        assert_eq!(LOOT.len(), 3);
+        assert_eq!(LOOT.total_weight(), 10.0);
+        let mut rng = SplitMix64::new(8);
+        let copper = (0..1000).filter(|_| *LOOT.pick_with(&mut rng) == "copper").count();
+        assert!((530..670).contains(&copper));
+        assert!(["copper", "silver", "gold"].contains(LOOT.random_pick()));
+
+        let heads = (0..1000).filter(|_| *COIN.pick_with(&mut rng)).count();
+        assert!((430..570).contains(&heads));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_static_distribution_rejects_bad_weight() {
+        StaticDistribution::new(&[('a', 1.0), ('b', -1.0)]);
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn test_static_distribution_macro() {
+        use crate::static_distribution;
+
+        #[derive(Debug, PartialEq)]
+        enum Rarity {Common, Rare}
+        static DROPS: StaticTable<Rarity> = static_distribution![Rarity::Common => 9, Rarity::Rare => 1.0];
+        assert_eq!(DROPS.values(), &[Rarity::Common, Rarity::Rare]);
+        assert_eq!(DROPS.weight_at(0), Some(9.0));
+        assert_eq!(DROPS.total_weight(), 10.0);
+        assert!((0..100).filter(|_| *DROPS.random_pick() == Rarity::Common).count() > 70);
+    }
+}
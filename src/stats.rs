@@ -0,0 +1,93 @@
+// Summary statistics of a numeric quantity `f(value)`, weighting each value by its probability.
+
+use std::fmt::Debug;
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// The smallest mapped value whose cumulative probability reaches `q`, so `q = 0.95`
+    /// gives the weighted 95th percentile. Returns `None` for an empty distribution.
+    pub fn weighted_quantile<F:Fn(&T)->f64>(&self, q: f64, f: F) -> Option<f64> {
+        assert!((0.0..=1.0).contains(&q));
+        let mut mapped: Vec<(f64, f64)> = self.originals.iter().map(|(value, weight)| (f(value), *weight)).collect();
+        assert!(mapped.iter().all(|(x, _)| !x.is_nan()));
+        mapped.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let target = q * self.total_weight;
+        let mut cumulative = 0.0;
+        for (x, weight) in mapped.iter() {
+            cumulative += weight;
+            if cumulative >= target {
+                return Some(*x);
+            }
+        }
+        mapped.last().map(|(x, _)| *x)
+    }
+
+    pub fn mean<F:Fn(&T)->f64>(&self, f: F) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.probabilities().map(|(value, p)| p * f(value)).sum())
+    }
+
+    pub fn variance<F:Fn(&T)->f64>(&self, f: F) -> Option<f64> {
+        let mean = self.mean(&f)?;
+        Some(self.central_moment(&f, mean, 2))
+    }
+
+    /// Returns `None` if the distribution is empty or the variance is zero.
+    pub fn skewness<F:Fn(&T)->f64>(&self, f: F) -> Option<f64> {
+        let (mean, variance) = self.spread(&f)?;
+        Some(self.central_moment(&f, mean, 3) / variance.powf(1.5))
+    }
+
+    /// The excess kurtosis, which is zero for a normal distribution. Returns `None` if the
+    /// distribution is empty or the variance is zero.
+    pub fn kurtosis<F:Fn(&T)->f64>(&self, f: F) -> Option<f64> {
+        let (mean, variance) = self.spread(&f)?;
+        Some(self.central_moment(&f, mean, 4) / (variance * variance) - 3.0)
+    }
+
+    fn spread<F:Fn(&T)->f64>(&self, f: &F) -> Option<(f64, f64)> {
+        let mean = self.mean(f)?;
+        let variance = self.central_moment(f, mean, 2);
+        (variance > 0.0).then_some((mean, variance))
+    }
+
+    fn central_moment<F:Fn(&T)->f64>(&self, f: &F, mean: f64, order: i32) -> f64 {
+        self.probabilities().map(|(value, p)| p * (f(value) - mean).powi(order)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Distribution;
+
+    #[test]
+    fn test_weighted_quantile() {
+        let latencies: Distribution<u32> = vec![(10, 90.0), (50, 8.0), (400, 2.0)].into();
+        let ms = |latency: &u32| *latency as f64;
+        assert_eq!(latencies.weighted_quantile(0.5, ms), Some(10.0));
+        assert_eq!(latencies.weighted_quantile(0.95, ms), Some(50.0));
+        assert_eq!(latencies.weighted_quantile(0.99, ms), Some(400.0));
+        assert_eq!(latencies.weighted_quantile(0.0, ms), Some(10.0));
+        assert_eq!(latencies.weighted_quantile(0.5, |latency| -(*latency as f64)), Some(-10.0));
+        assert_eq!(Distribution::<u32>::new().weighted_quantile(0.5, ms), None);
+    }
+
+    #[test]
+    fn test_moments() {
+        let coin: Distribution<u32> = vec![(0, 1.0), (1, 1.0)].into();
+        let x = |value: &u32| *value as f64;
+        assert_eq!(coin.mean(x), Some(0.5));
+        assert_eq!(coin.variance(x), Some(0.25));
+        assert_eq!(coin.skewness(x), Some(0.0));
+        assert_eq!(coin.kurtosis(x), Some(-2.0));
+
+        let skewed: Distribution<u32> = vec![(0, 9.0), (10, 1.0)].into();
+        assert!(skewed.skewness(x).unwrap() > 2.0);
+        let constant: Distribution<u32> = vec![(3, 1.0)].into();
+        assert_eq!(constant.variance(x), Some(0.0));
+        assert_eq!(constant.skewness(x), None);
+        assert_eq!(Distribution::<u32>::new().mean(x), None);
+    }
+}
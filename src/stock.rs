@@ -0,0 +1,113 @@
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::Rng;
+use crate::Distribution;
+
+/// A distribution whose values each have a limited number of copies. Every pick uses up one
+/// copy, and a value with none left cannot be picked until it is restocked.
+#[derive(Clone, Debug)]
+pub struct StockedDistribution<T> {
+    pub(crate) available: Distribution<T>,
+    weights: BTreeMap<T, f64>,
+    stock: BTreeMap<T, u64>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> StockedDistribution<T> {
+    pub fn new() -> Self {
+        StockedDistribution {available: Distribution::new(), weights: BTreeMap::new(), stock: BTreeMap::new()}
+    }
+
+    /// Sets the weight and the number of copies of `value`, replacing any previous ones.
+    pub fn add(&mut self, value: &T, weight: f64, copies: u64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.weights.insert(value.clone(), weight);
+        self.stock.insert(value.clone(), copies);
+        if copies > 0 {
+            self.available.add(value, weight);
+        } else {
+            self.available.remove(value);
+        }
+    }
+
+    pub fn stock(&self, value: &T) -> Option<u64> {
+        self.stock.get(value).copied()
+    }
+
+    /// The number of copies left across all values.
+    pub fn remaining(&self) -> u64 {
+        self.stock.values().sum()
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.available.is_empty()
+    }
+
+    /// Adds `copies` copies of a value already added, making it pickable again if it ran out.
+    pub fn restock(&mut self, value: &T, copies: u64) {
+        let stock = self.stock.get_mut(value).expect("restocking a value that was never added");
+        if *stock == 0 && copies > 0 {
+            self.available.add(value, self.weights[value]);
+        }
+        *stock += copies;
+    }
+
+    /// Returns `None` once every value is out of stock.
+    pub fn pick(&mut self) -> Option<T> {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&mut self, rng: &mut R) -> Option<T> {
+        if self.available.is_empty() {
+            return None;
+        }
+        let picked = self.available.notify_pick(self.available.pick_ref(rng)).clone();
+        let stock = self.stock.get_mut(&picked).unwrap();
+        *stock -= 1;
+        if *stock == 0 {
+            self.available.remove(&picked);
+        }
+        Some(picked)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for StockedDistribution<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SplitMix64, StockedDistribution};
+
+    #[test]
+    fn test_stock_runs_out() {
+        let mut prizes = StockedDistribution::new();
+        prizes.add(&"grand", 1.0, 1);
+        prizes.add(&"small", 100.0, 3);
+        prizes.add(&"none", 5.0, 0);
+        assert_eq!(prizes.remaining(), 4);
+        let mut drawn: Vec<&str> = (0..4).map(|_| prizes.pick().unwrap()).collect();
+        drawn.sort();
+        assert_eq!(drawn, vec!["grand", "small", "small", "small"]);
+        assert!(prizes.is_exhausted());
+        assert_eq!(prizes.pick(), None);
+
+        prizes.restock(&"grand", 2);
+        assert_eq!(prizes.stock(&"grand"), Some(2));
+        assert_eq!(prizes.pick(), Some("grand"));
+        assert_eq!(prizes.remaining(), 1);
+    }
+
+    #[test]
+    fn test_seeded_draws_repeat() {
+        let mut prizes = StockedDistribution::new();
+        for (i, prize) in ["a", "b", "c", "d"].iter().enumerate() {
+            prizes.add(prize, 1.0 + i as f64, 2);
+        }
+        let mut replay = prizes.clone();
+        let (mut rng, mut replay_rng) = (SplitMix64::new(17), SplitMix64::new(17));
+        let drawn: Vec<&str> = std::iter::from_fn(|| prizes.pick_with(&mut rng)).collect();
+        assert_eq!(drawn.len(), 8);
+        assert_eq!(drawn, std::iter::from_fn(|| replay.pick_with(&mut replay_rng)).collect::<Vec<_>>());
+    }
+}
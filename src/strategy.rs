@@ -0,0 +1,53 @@
+//! proptest strategies that generate valid distributions: non-empty, with positive, finite weights.
+
+use std::{fmt::Debug, ops::Range};
+use proptest::{arbitrary::{any, Arbitrary}, collection::btree_map, strategy::{BoxedStrategy, Strategy}};
+use crate::Distribution;
+
+/// Weights are drawn from this range so that totals can never overflow.
+pub const WEIGHT_RANGE: Range<f64> = 0.001..1000.0;
+
+/// Distributions over distinct values from `values`, with a number of entries in `size`.
+pub fn distribution<S>(values: S, size: Range<usize>) -> impl Strategy<Value = Distribution<S::Value>>
+    where S: Strategy, S::Value: Clone + PartialEq + Eq + PartialOrd + Ord + Debug {
+    assert!(size.start > 0);
+    btree_map(values, WEIGHT_RANGE, size).prop_map(|weights| {
+        let mut result = Distribution::new();
+        for (value, weight) in weights.iter() {
+            result.add(value, *weight);
+        }
+        result
+    })
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Arbitrary + 'static> Arbitrary for Distribution<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        distribution(any::<T>(), 1..16).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use crate::Distribution;
+    use super::{distribution, WEIGHT_RANGE};
+
+    proptest! {
+        #[test]
+        fn generated_distributions_are_valid(dist in distribution(0u8..50, 1..10)) {
+            prop_assert!(!dist.is_empty());
+            prop_assert!(dist.originals.len() < 10);
+            prop_assert!(dist.originals.values().all(|w| WEIGHT_RANGE.contains(w)));
+            prop_assert!(dist.originals.contains_key(&dist.random_pick()));
+        }
+
+        #[test]
+        fn arbitrary_distributions_are_valid(dist in any::<Distribution<String>>()) {
+            prop_assert!(!dist.is_empty());
+            prop_assert!(dist.total_weight.is_finite());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+// Survey-sampling designs over the cumulative weight scale.
+
+use std::fmt::Debug;
+use rand::Rng;
+use crate::Distribution;
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Systematic sampling: `n` points spaced `total_weight / n` apart from a single random
+    /// start. Each selected value is reported once, with the number of points that hit it, so
+    /// a value heavier than the spacing can have several hits. Values appear in ascending order.
+    pub fn sample_systematic(&self, n: usize) -> Vec<(T, usize)> {
+        self.sample_systematic_with(n, &mut rand::thread_rng())
+    }
+
+    pub fn sample_systematic_with<R:Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<(T, usize)> {
+        assert!(n > 0 && !self.is_empty());
+        let spacing = self.total_weight / n as f64;
+        let start = rng.gen_range(0.0..spacing);
+        let mut result: Vec<(T, usize)> = Vec::new();
+        for i in 0..n {
+            let value = self.value_at((start + i as f64 * spacing).min(self.total_weight))
+                .unwrap_or_else(|| self.values.last().unwrap());
+            match result.last_mut() {
+                Some((last, hits)) if last == value => *hits += 1,
+                _ => result.push((self.notify_pick(value).clone(), 1))
+            }
+        }
+        result
+    }
+
+    /// Draws `n` distinct values with probability proportional to weight, returning each with
+    /// its first-order inclusion probability. Values heavy enough to have an inclusion
+    /// probability of one are taken with certainty; the rest are drawn by systematic sampling on
+    /// the cumulative scale of their inclusion probabilities, so each is drawn at most once.
+    pub fn sample_pps_without_replacement(&self, n: usize) -> Vec<(T, f64)> {
+        self.sample_pps_without_replacement_with(n, &mut rand::thread_rng())
+    }
+
+    pub fn sample_pps_without_replacement_with<R:Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<(T, f64)> {
+        assert!(n <= self.originals.len());
+        let inclusion = self.inclusion_probabilities(n);
+        let certain: Vec<(T, f64)> = inclusion.iter().filter(|(_, p)| *p >= 1.0).map(|(value, p)| ((*value).clone(), *p)).collect();
+        let uncertain: Vec<&(&T, f64)> = inclusion.iter().filter(|(_, p)| *p < 1.0).collect();
+
+        let mut result = certain;
+        let mut point = rng.gen::<f64>();
+        let mut cumulative = 0.0;
+        for (i, (value, p)) in uncertain.iter().enumerate() {
+            cumulative += p;
+            // The last test also catches a final point that rounding left past the end.
+            let short = n - result.len() == uncertain.len() - i;
+            if result.len() < n && (point < cumulative || short) {
+                result.push(((*value).clone(), *p));
+                point += 1.0;
+            }
+        }
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    // n * w / total for each value, capped at one. Capping a value frees mass that is spread
+    // over the remaining values, so the cap is applied until no uncapped value exceeds it.
+    fn inclusion_probabilities(&self, n: usize) -> Vec<(&T, f64)> {
+        let mut certain = vec![false; self.originals.len()];
+        loop {
+            let slots = n - certain.iter().filter(|c| **c).count();
+            let weight: f64 = self.originals.values().zip(certain.iter()).filter(|(_, c)| !**c).map(|(w, _)| w).sum();
+            let probabilities: Vec<f64> = self.originals.values().zip(certain.iter())
+                .map(|(w, c)| if *c {1.0} else {slots as f64 * w / weight})
+                .collect();
+            let newly_certain: Vec<usize> = probabilities.iter().enumerate().filter(|(i, p)| !certain[*i] && **p >= 1.0).map(|(i, _)| i).collect();
+            if newly_certain.is_empty() {
+                return self.originals.keys().zip(probabilities).collect();
+            }
+            newly_certain.into_iter().for_each(|i| certain[i] = true);
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HorvitzThompsonEstimate {
+    /// The estimated population total of `y`.
+    pub total: f64,
+    /// The estimated number of units in the population.
+    pub population_size: f64,
+    /// `total / population_size`, the Hájek estimate of the population mean.
+    pub mean: f64
+}
+
+/// Estimates population quantities from a sample of `(unit, inclusion probability)` pairs,
+/// such as the result of `sample_pps_without_replacement()`, by weighting each unit's `y` by
+/// the inverse of its inclusion probability.
+pub fn horvitz_thompson_estimate<T, F:Fn(&T)->f64>(sample: &[(T, f64)], y: F) -> HorvitzThompsonEstimate {
+    assert!(sample.iter().all(|(_, p)| *p > 0.0 && *p <= 1.0));
+    let total = sample.iter().map(|(unit, p)| y(unit) / p).sum();
+    let population_size: f64 = sample.iter().map(|(_, p)| 1.0 / p).sum();
+    HorvitzThompsonEstimate {total, population_size, mean: total / population_size}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{horvitz_thompson_estimate, Distribution, SplitMix64};
+
+    #[test]
+    fn test_sample_systematic() {
+        let dist: Distribution<char> = vec![('a', 1.0), ('b', 2.5), ('c', 0.5)].into();
+        for _ in 0..50 {
+            let sample = dist.sample_systematic(4);
+            assert_eq!(sample.iter().map(|(_, hits)| hits).sum::<usize>(), 4);
+            let b_hits = sample.iter().find(|(value, _)| *value == 'b').unwrap().1;
+            assert!(b_hits == 2 || b_hits == 3);
+            assert!(sample.iter().any(|(value, _)| *value == 'a'));
+        }
+        let seeded = |seed| dist.sample_systematic_with(3, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(10), seeded(10));
+    }
+
+    #[test]
+    fn test_sample_pps_without_replacement() {
+        let dist: Distribution<char> = vec![('a', 10.0), ('b', 1.0), ('c', 1.0), ('d', 2.0)].into();
+        let inclusion = dist.inclusion_probabilities(2);
+        assert_eq!(inclusion, vec![(&'a', 1.0), (&'b', 0.25), (&'c', 0.25), (&'d', 0.5)]);
+
+        let mut d_count = 0;
+        for _ in 0..2000 {
+            let sample = dist.sample_pps_without_replacement(2);
+            assert_eq!(sample.len(), 2);
+            assert_eq!(sample[0], ('a', 1.0));
+            assert_ne!(sample[0].0, sample[1].0);
+            d_count += sample.iter().filter(|(value, _)| *value == 'd').count();
+        }
+        assert!((900..1100).contains(&d_count));
+        assert_eq!(dist.sample_pps_without_replacement(4).len(), 4);
+        let seeded = |seed| dist.sample_pps_without_replacement_with(3, &mut SplitMix64::new(seed));
+        assert_eq!(seeded(10), seeded(10));
+    }
+
+    #[test]
+    fn test_horvitz_thompson() {
+        let estimate = horvitz_thompson_estimate(&[('a', 1.0), ('d', 0.5)], |unit| if *unit == 'a' {10.0} else {3.0});
+        assert_eq!(estimate.total, 16.0);
+        assert_eq!(estimate.population_size, 3.0);
+
+        let sizes: Vec<(u32, f64)> = (1..=20).map(|i| (i, i as f64)).collect();
+        let dist: Distribution<u32> = sizes.into();
+        let trials = 2000;
+        let mean_total = (0..trials)
+            .map(|_| horvitz_thompson_estimate(&dist.sample_pps_without_replacement(5), |unit| *unit as f64 * 2.0 + 1.0).total)
+            .sum::<f64>() / trials as f64;
+        assert!((mean_total - 440.0).abs() < 10.0);
+    }
+}
@@ -0,0 +1,155 @@
+//! Statistical assertions for test suites that consume distributions.
+
+use std::{fmt::{Debug, Write}, collections::BTreeMap};
+use crate::Distribution;
+
+/// Draws `picks` values from `dist` and checks them with `assert_counts_match_weights()`.
+pub fn assert_matches_weights<T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug>(dist: &Distribution<T>, picks: usize, tolerance: f64) {
+    assert!(picks > 0);
+    let mut observed: BTreeMap<T, usize> = BTreeMap::new();
+    for _ in 0..picks {
+        *observed.entry(dist.random_pick()).or_insert(0) += 1;
+    }
+    assert_counts_match_weights(dist, &observed, tolerance);
+}
+
+/// Panics, printing expected and observed counts, if a chi-square goodness-of-fit test rejects
+/// `observed` as picks from `dist` at significance level `tolerance` (for example, 0.001).
+pub fn assert_counts_match_weights<T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug>(dist: &Distribution<T>, observed: &BTreeMap<T, usize>, tolerance: f64) {
+    assert!(tolerance > 0.0 && tolerance < 1.0);
+    let p = chi_square_p_value(dist, observed);
+    if p < tolerance {
+        let total: usize = observed.values().sum();
+        let mut report = format!("picks do not match weights (p = {p:.3e} < {tolerance})\n");
+        writeln!(report, "{:<20} {:>12} {:>12}", "value", "expected", "observed").unwrap();
+        for (value, probability) in dist.probabilities() {
+            let seen = observed.get(value).copied().unwrap_or(0);
+            writeln!(report, "{:<20} {:>12.1} {:>12}", format!("{value:?}"), probability * total as f64, seen).unwrap();
+        }
+        for (value, seen) in observed.iter().filter(|(value, _)| !dist.originals.contains_key(value)) {
+            writeln!(report, "{:<20} {:>12.1} {:>12}", format!("{value:?}"), 0.0, seen).unwrap();
+        }
+        panic!("{report}");
+    }
+}
+
+/// The p-value of Pearson's chi-square test for `observed` pick counts drawn from `dist`.
+/// Counts for values absent from `dist` make the p-value zero.
+pub fn chi_square_p_value<T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug>(dist: &Distribution<T>, observed: &BTreeMap<T, usize>) -> f64 {
+    if observed.keys().any(|value| !dist.originals.contains_key(value)) {
+        return 0.0;
+    }
+    let total: usize = observed.values().sum();
+    let statistic: f64 = dist.probabilities()
+        .map(|(value, probability)| {
+            let expected = probability * total as f64;
+            let seen = observed.get(value).copied().unwrap_or(0) as f64;
+            (seen - expected).powi(2) / expected
+        })
+        .sum();
+    let degrees = dist.originals.len().saturating_sub(1);
+    if degrees == 0 {1.0} else {upper_regularized_gamma(degrees as f64 / 2.0, statistic / 2.0)}
+}
+
+// Q(a, x) via the series or continued fraction in Numerical Recipes, section 6.2.
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefix = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        while term.abs() > sum.abs() * 1e-15 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+        }
+        1.0 - sum * prefix
+    } else {
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = if d.abs() < tiny {tiny} else {d};
+            c = b + an / c;
+            c = if c.abs() < tiny {tiny} else {c};
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        prefix * h
+    }
+}
+
+// Lanczos approximation with g = 7, n = 9.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+        -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..].iter().enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::Distribution;
+    use super::{assert_counts_match_weights, assert_matches_weights, chi_square_p_value, ln_gamma, upper_regularized_gamma};
+
+    fn example() -> Distribution<char> {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 2.0);
+        dist.add(&'c', 7.0);
+        dist
+    }
+
+    #[test]
+    fn test_special_functions() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+        // Chi-square with 2 degrees of freedom has survival function exp(-x / 2).
+        assert!((upper_regularized_gamma(1.0, 3.0) - (-3.0f64).exp()).abs() < 1e-12);
+        assert!((upper_regularized_gamma(1.0, 0.2) - (-0.2f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_p_values() {
+        let dist = example();
+        let exact: BTreeMap<char, usize> = [('a', 100), ('b', 200), ('c', 700)].into_iter().collect();
+        assert!((chi_square_p_value(&dist, &exact) - 1.0).abs() < 1e-12);
+        let skewed: BTreeMap<char, usize> = [('a', 300), ('b', 200), ('c', 500)].into_iter().collect();
+        assert!(chi_square_p_value(&dist, &skewed) < 1e-10);
+        let stray: BTreeMap<char, usize> = [('z', 1)].into_iter().collect();
+        assert_eq!(chi_square_p_value(&dist, &stray), 0.0);
+    }
+
+    #[test]
+    fn test_assert_matches_weights() {
+        assert_matches_weights(&example(), 5000, 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "picks do not match weights")]
+    fn test_assert_rejects_wrong_counts() {
+        let skewed: BTreeMap<char, usize> = [('a', 300), ('b', 200), ('c', 500)].into_iter().collect();
+        assert_counts_match_weights(&example(), &skewed, 1e-6);
+    }
+}
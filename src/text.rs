@@ -0,0 +1,164 @@
+use std::{fmt::{self, Debug, Display}, error::Error, io::{self, BufRead, Write}};
+use crate::{Distribution, ExportOptions};
+
+#[derive(Debug)]
+pub enum FromLinesError<E> {
+    Io(io::Error),
+    /// Every line that was rejected, as (1-based line number, error).
+    Parse(Vec<(usize, LineError<E>)>)
+}
+
+/// Why a single line was rejected: the parser failed, or it produced a weight that is not
+/// positive and finite.
+#[derive(Debug)]
+pub enum LineError<E> {
+    Parse(E),
+    InvalidWeight(f64)
+}
+
+impl <E:Display> Display for LineError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineError::Parse(e) => write!(f, "{e}"),
+            LineError::InvalidWeight(weight) => write!(f, "weight {weight} is not positive and finite")
+        }
+    }
+}
+
+impl <E:Display> Display for FromLinesError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromLinesError::Io(e) => write!(f, "read failed: {e}"),
+            FromLinesError::Parse(errors) => {
+                write!(f, "{} line(s) were rejected", errors.len())?;
+                for (line, e) in errors.iter() {
+                    write!(f, "\n  line {line}: {e}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl <E:Debug + Display> Error for FromLinesError<E> {}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Builds a distribution one line at a time, skipping blank lines. Parsing continues past
+    /// bad lines so that every parse error and invalid weight in the stream is reported
+    /// together.
+    pub fn from_lines<R, E, F>(reader: R, mut parse: F) -> Result<Self, FromLinesError<E>>
+        where R: BufRead, F: FnMut(&str) -> Result<(T, f64), E> {
+        let mut result = Distribution::new();
+        let mut errors = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(FromLinesError::Io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse(&line) {
+                Ok((_, weight)) if !(weight.is_finite() && weight > 0.0) => {
+                    errors.push((i + 1, LineError::InvalidWeight(weight)))
+                }
+                Ok((value, weight)) => {
+                    if errors.is_empty() {
+                        result.add(&value, weight);
+                    }
+                }
+                Err(e) => errors.push((i + 1, LineError::Parse(e)))
+            }
+        }
+        if !errors.is_empty() {
+            return Err(FromLinesError::Parse(errors));
+        }
+        result.trace_constructed("lines");
+        Ok(result)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// One line per value: its `Debug` form, a bar scaled so the most likely value's bar is
+    /// `width` characters long, and its probability as a percentage.
+    pub fn render_histogram(&self, width: usize) -> String {
+        let labels: Vec<String> = self.originals.keys().map(|value| format!("{value:?}")).collect();
+        let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+        let max_weight = self.originals.values().copied().fold(0.0, f64::max);
+        let mut result = String::new();
+        for (label, weight) in labels.iter().zip(self.originals.values()) {
+            let bar = "#".repeat((weight / max_weight * width as f64).round() as usize);
+            result.push_str(&format!("{label:<label_width$} {bar:<width$} {:>6.2}%\n", 100.0 * weight / self.total_weight));
+        }
+        result
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Display> Distribution<T> {
+    /// Writes one `value,weight` line per value, in ascending value order and without a header.
+    pub fn write_csv<W:Write>(&self, mut writer: W, options: ExportOptions) -> io::Result<()> {
+        for (value, weight) in self.exported_weights(options) {
+            writeln!(writer, "{value},{weight}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Distribution, ExportOptions, FromLinesError, LineError};
+
+    fn parse(line: &str) -> Result<(String, f64), String> {
+        let (value, weight) = line.split_once(',').ok_or_else(|| format!("no comma in {line:?}"))?;
+        let weight = weight.trim().parse::<f64>().map_err(|e| e.to_string())?;
+        Ok((value.to_string(), weight))
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let input = "a,1.0\nb, 0.5\n\nc,3.5\n";
+        let dist = Distribution::from_lines(input.as_bytes(), parse).unwrap();
+        assert_eq!(dist.total_weight, 5.0);
+        assert_eq!(dist.originals.get("b"), Some(&0.5));
+    }
+
+    #[test]
+    fn test_from_lines_accumulates_errors() {
+        let input = "a,1.0\nb\nc,3.5\nd,x\n";
+        match Distribution::from_lines(input.as_bytes(), parse) {
+            Err(FromLinesError::Parse(errors)) => {
+                assert_eq!(errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![2, 4]);
+            }
+            other => panic!("expected parse errors, got {other:?}")
+        }
+    }
+
+    #[test]
+    fn test_from_lines_rejects_bad_weights() {
+        let input = "a,1.0\nb,0\nc,-2\nd,NaN\ne,inf\n";
+        match Distribution::from_lines(input.as_bytes(), parse) {
+            Err(FromLinesError::Parse(errors)) => {
+                assert_eq!(errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+                assert!(errors.iter().all(|(_, e)| matches!(e, LineError::InvalidWeight(_))));
+                assert_eq!(errors[1].1.to_string(), "weight -2 is not positive and finite");
+            }
+            other => panic!("expected weight errors, got {other:?}")
+        }
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let dist = Distribution::from_lines("b,2\na,1\nc,0.25\n".as_bytes(), parse).unwrap();
+        let mut raw = Vec::new();
+        dist.write_csv(&mut raw, ExportOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(raw).unwrap(), "a,1\nb,2\nc,0.25\n");
+
+        let mut rounded = Vec::new();
+        dist.write_csv(&mut rounded, ExportOptions::normalized().with_decimals(3)).unwrap();
+        assert_eq!(String::from_utf8(rounded).unwrap(), "a,0.308\nb,0.615\nc,0.077\n");
+    }
+
+    #[test]
+    fn test_render_histogram() {
+        let dist = Distribution::from_lines("a,3\nbb,1\n".as_bytes(), parse).unwrap();
+        assert_eq!(dist.render_histogram(8), "\"a\"  ########  75.00%\n\"bb\" ###       25.00%\n");
+        assert_eq!(Distribution::<String>::new().render_histogram(8), "");
+    }
+}
@@ -0,0 +1,102 @@
+use std::{fmt::Debug, collections::BTreeMap};
+use rand::Rng;
+use crate::{pick_weighted, Distribution};
+
+/// Two-stage selection: roll a weighted tier (rarity), then roll an item within it. Only tiers
+/// that have both a weight and at least one item take part in the first stage.
+#[derive(Clone, Debug)]
+pub struct TieredDistribution<G, T> {
+    tier_weights: BTreeMap<G, f64>,
+    items: BTreeMap<G, Distribution<T>>
+}
+
+impl <G:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> TieredDistribution<G, T> {
+    pub fn new() -> Self {
+        TieredDistribution {tier_weights: BTreeMap::new(), items: BTreeMap::new()}
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active_tiers().next().is_none()
+    }
+
+    pub fn set_tier_weight(&mut self, tier: &G, weight: f64) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.tier_weights.insert(tier.clone(), weight);
+    }
+
+    pub fn add_tiered(&mut self, value: &T, tier: &G, weight: f64) {
+        self.items.entry(tier.clone()).or_default().add(value, weight);
+    }
+
+    pub fn tier(&self, tier: &G) -> Option<&Distribution<T>> {
+        self.items.get(tier)
+    }
+
+    pub fn tier_probabilities(&self) -> BTreeMap<G, f64> {
+        let total: f64 = self.active_tiers().map(|(_, weight)| weight).sum();
+        self.active_tiers().map(|(tier, weight)| (tier.clone(), weight / total)).collect()
+    }
+
+    pub fn pick_from_tier(&self, tier: &G) -> Option<T> {
+        self.items.get(tier).map(|items| items.random_pick())
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    /// As `random_pick()`, drawing both the tier and the item from `rng`.
+    pub fn pick_with<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        assert!(!self.is_empty());
+        self.pick_ref(rng).clone()
+    }
+
+    pub(crate) fn pick_ref<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        let tier = pick_weighted(self.active_tiers(), rng).unwrap();
+        self.items[tier].pick_ref(rng)
+    }
+
+    pub(crate) fn active_tiers(&self) -> impl Iterator<Item=(&G, f64)> {
+        self.tier_weights.iter()
+            .filter(|(tier, _)| self.items.contains_key(*tier))
+            .map(|(tier, weight)| (tier, *weight))
+    }
+}
+
+impl <G:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Default for TieredDistribution<G, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SplitMix64, TieredDistribution};
+
+    #[test]
+    fn test_tiers() {
+        let mut loot = TieredDistribution::new();
+        loot.set_tier_weight(&"common", 3.0);
+        loot.set_tier_weight(&"rare", 1.0);
+        loot.set_tier_weight(&"mythic", 5.0);
+        loot.add_tiered(&"stick", &"common", 1.0);
+        loot.add_tiered(&"stone", &"common", 1.0);
+        loot.add_tiered(&"sword", &"rare", 1.0);
+
+        let probs = loot.tier_probabilities();
+        assert_eq!(probs.len(), 2);
+        assert_eq!(probs["common"], 0.75);
+        assert_eq!(probs["rare"], 0.25);
+
+        assert_eq!(loot.pick_from_tier(&"rare"), Some("sword"));
+        assert_eq!(loot.pick_from_tier(&"mythic"), None);
+        let swords = (0..400).filter(|_| loot.random_pick() == "sword").count();
+        assert!(swords > 60 && swords < 140);
+
+        let seeded = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..20).map(|_| loot.pick_with(&mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(seeded(9), seeded(9));
+    }
+}
@@ -0,0 +1,176 @@
+// Exponential decay shrinks every weight by the same factor over any stretch of time, so the
+// probabilities never change as time passes; only the stamps relative to one another matter.
+// The table therefore holds each weight grown by 2^((stamp - reference) / half_life) and divided
+// by a common scale, and is rebuilt around the latest stamp and the largest weight whenever a new
+// stamp would push that factor out of range or a table weight or total would overflow.
+
+use std::{fmt::Debug, collections::BTreeMap, time::{Duration, Instant}};
+use rand::Rng;
+use crate::Distribution;
+
+// Largest doubling exponent allowed before rebasing, leaving room below f64::MAX.
+const MAX_EXPONENT: f64 = 512.0;
+
+/// Values whose weights halve every `half_life` after they are stamped, with no need to sweep
+/// the table as time passes.
+#[derive(Clone, Debug)]
+pub struct TimeWeighted<T> {
+    half_life: Duration,
+    entries: BTreeMap<T, (f64, Instant)>,
+    reference: Option<Instant>,
+    scale: f64,
+    table: Distribution<T>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> TimeWeighted<T> {
+    pub fn new(half_life: Duration) -> Self {
+        assert!(!half_life.is_zero());
+        TimeWeighted {half_life, entries: BTreeMap::new(), reference: None, scale: 1.0, table: Distribution::new()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stamps `value` with `weight` as of now, replacing any earlier stamp.
+    pub fn insert(&mut self, value: &T, weight: f64) {
+        self.insert_at(value, weight, Instant::now());
+    }
+
+    pub fn insert_at(&mut self, value: &T, weight: f64, at: Instant) {
+        assert!(weight.is_finite() && weight > 0.0);
+        self.entries.insert(value.clone(), (weight, at));
+        let reference = *self.reference.get_or_insert(at);
+        self.table.remove(value);
+        let scaled = self.scaled(weight, at, reference);
+        if self.doublings(at, reference) > MAX_EXPONENT || !(self.table.total_weight + scaled).is_finite() {
+            self.rebase();
+        } else if scaled > 0.0 {
+            self.table.add(value, scaled);
+        }
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<f64> {
+        let (weight, _) = self.entries.remove(value)?;
+        self.table.remove(value);
+        if self.table.is_empty() && !self.is_empty() {
+            self.rebase();
+        }
+        Some(weight)
+    }
+
+    /// The decayed weight of `value` at `now`. Stamps later than `now` have not decayed yet.
+    pub fn weight_at(&self, value: &T, now: Instant) -> Option<f64> {
+        self.entries.get(value).map(|(weight, at)| {
+            weight * 0.5f64.powf(now.saturating_duration_since(*at).as_secs_f64() / self.half_life.as_secs_f64())
+        })
+    }
+
+    /// Picks by decayed weight. Values so much older than the rest that their share rounds to
+    /// zero are never picked.
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        assert!(!self.is_empty());
+        self.table.pick_ref(rng).clone()
+    }
+
+    /// The number of values whose share has not rounded to zero.
+    pub(crate) fn pickable_len(&self) -> usize {
+        self.table.originals.len()
+    }
+
+    /// Like `weight_at()`, but `None` for values whose share has rounded to zero.
+    pub(crate) fn pickable_weight_at(&self, value: &T, now: Instant) -> Option<f64> {
+        self.table.get(value).and(self.weight_at(value, now))
+    }
+
+    fn doublings(&self, at: Instant, reference: Instant) -> f64 {
+        let offset = match at.checked_duration_since(reference) {
+            Some(later) => later.as_secs_f64(),
+            None => -reference.duration_since(at).as_secs_f64()
+        };
+        offset / self.half_life.as_secs_f64()
+    }
+
+    fn scaled(&self, weight: f64, at: Instant, reference: Instant) -> f64 {
+        weight / self.scale * 2f64.powf(self.doublings(at, reference))
+    }
+
+    // With the latest stamp as reference, no entry grows, and dividing by the largest weight
+    // keeps each table weight at most one, so neither they nor their total can overflow.
+    fn rebase(&mut self) {
+        let reference = self.entries.values().map(|(_, at)| *at).max().unwrap();
+        self.reference = Some(reference);
+        self.scale = self.entries.values().map(|(weight, _)| *weight).fold(0.0, f64::max);
+        self.table = Distribution::new();
+        for (value, (weight, at)) in self.entries.iter() {
+            let scaled = self.scaled(*weight, *at, reference);
+            if scaled > 0.0 {
+                self.table.add(value, scaled);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::TimeWeighted;
+
+    #[test]
+    fn test_decay() {
+        let start = Instant::now();
+        let mut recent = TimeWeighted::new(Duration::from_secs(10));
+        recent.insert_at(&'a', 4.0, start);
+        recent.insert_at(&'b', 1.0, start + Duration::from_secs(20));
+        assert_eq!(recent.weight_at(&'a', start + Duration::from_secs(20)), Some(1.0));
+        assert_eq!(recent.weight_at(&'b', start + Duration::from_secs(20)), Some(1.0));
+        assert_eq!(recent.weight_at(&'b', start), Some(1.0));
+
+        let a_count = (0..1000).filter(|_| recent.random_pick() == 'a').count();
+        assert!((400..600).contains(&a_count));
+
+        recent.insert_at(&'a', 4.0, start + Duration::from_secs(20));
+        assert!((0..100).filter(|_| recent.random_pick() == 'a').count() > 50);
+        assert_eq!(recent.remove(&'a'), Some(4.0));
+        assert!((0..20).all(|_| recent.random_pick() == 'b'));
+    }
+
+    #[test]
+    fn test_rebase_over_long_spans() {
+        let start = Instant::now();
+        let mut recent = TimeWeighted::new(Duration::from_millis(1));
+        recent.insert_at(&1, 1.0, start);
+        recent.insert_at(&2, 1.0, start + Duration::from_secs(5));
+        recent.insert_at(&3, 1.0, start + Duration::from_secs(5));
+        assert_eq!(recent.len(), 3);
+        assert!((0..100).all(|_| recent.random_pick() != 1));
+        recent.remove(&2);
+        recent.remove(&3);
+        assert_eq!(recent.random_pick(), 1);
+    }
+
+    #[test]
+    fn test_rebase_for_huge_weights() {
+        let start = Instant::now();
+        let mut recent = TimeWeighted::new(Duration::from_secs(1));
+        recent.insert_at(&1, 1.0, start);
+        recent.insert_at(&2, 1e200, start + Duration::from_secs(500));
+        assert_eq!(recent.pickable_len(), 1);
+        assert!((0..20).all(|_| recent.random_pick() == 2));
+
+        recent.insert_at(&3, f64::MAX, start + Duration::from_secs(500));
+        recent.insert_at(&4, f64::MAX, start + Duration::from_secs(500));
+        assert_eq!(recent.len(), 4);
+        assert_eq!(recent.pickable_len(), 3);
+        let picks = (0..1000).filter(|_| recent.random_pick() == 3).count();
+        assert!((400..600).contains(&picks));
+    }
+}
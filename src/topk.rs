@@ -0,0 +1,108 @@
+// Space-saving algorithm from Metwally, Agrawal and El Abbadi,
+// "Efficient computation of frequent and top-k elements in data streams" (2005).
+
+use std::{fmt::Debug, collections::{BTreeMap, BTreeSet}};
+use crate::Distribution;
+
+/// Tracks approximate counts of the most frequent items in a stream using bounded memory.
+#[derive(Clone, Debug)]
+pub struct TopKCounter<T> {
+    capacity: usize,
+    counts: BTreeMap<T, (u64, u64)>,
+    // The same counts ordered by (count, value), so the item to evict is always the first.
+    by_count: BTreeSet<(u64, T)>
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> TopKCounter<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        TopKCounter {capacity, counts: BTreeMap::new(), by_count: BTreeSet::new()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn observe(&mut self, value: &T) {
+        if let Some((count, _)) = self.counts.get_mut(value) {
+            let entry = (*count, value.clone());
+            self.by_count.remove(&entry);
+            *count += 1;
+            self.by_count.insert((*count, entry.1));
+        } else if self.counts.len() < self.capacity {
+            self.counts.insert(value.clone(), (1, 0));
+            self.by_count.insert((1, value.clone()));
+        } else {
+            let (min_count, evicted) = self.by_count.pop_first().unwrap();
+            self.counts.remove(&evicted);
+            self.counts.insert(value.clone(), (min_count + 1, min_count));
+            self.by_count.insert((min_count + 1, value.clone()));
+        }
+    }
+
+    /// The estimated count of `value`, which overestimates the true count by at most `error(value)`.
+    pub fn count(&self, value: &T) -> Option<u64> {
+        self.counts.get(value).map(|(count, _)| *count)
+    }
+
+    pub fn error(&self, value: &T) -> Option<u64> {
+        self.counts.get(value).map(|(_, error)| *error)
+    }
+
+    /// Tracked items by descending estimated count.
+    pub fn top(&self) -> Vec<(T, u64)> {
+        let mut result: Vec<(T, u64)> = self.counts.iter().map(|(v, (count, _))| (v.clone(), *count)).collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        result
+    }
+
+    pub fn to_distribution(&self) -> Distribution<T> {
+        let mut result = Distribution::new();
+        for (value, (count, _)) in self.counts.iter() {
+            result.add(value, *count as f64);
+        }
+        result
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Extend<T> for TopKCounter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.observe(&value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TopKCounter;
+
+    #[test]
+    fn test_heavy_hitters() {
+        let mut counter = TopKCounter::new(6);
+        for i in 0..1000 {
+            counter.observe(&"common");
+            if i % 2 == 0 {
+                counter.observe(&"frequent");
+            }
+            counter.observe(&["n0", "n1", "n2", "n3", "n4", "n5", "n6", "n7", "n8", "n9"][i % 10]);
+        }
+        assert_eq!(counter.len(), 6);
+        let top = counter.top();
+        assert_eq!(top[0].0, "common");
+        assert_eq!(top[1].0, "frequent");
+        assert!(counter.count(&"common").unwrap() - counter.error(&"common").unwrap() <= 1000);
+        assert!(counter.count(&"common").unwrap() >= 1000);
+
+        assert_eq!(counter.by_count.len(), counter.counts.len());
+        assert!(counter.by_count.iter().all(|(count, value)| counter.count(value) == Some(*count)));
+
+        let dist = counter.to_distribution();
+        assert_eq!(dist.originals.len(), 6);
+        assert_eq!(dist.originals.get("common"), Some(&(counter.count(&"common").unwrap() as f64)));
+    }
+}
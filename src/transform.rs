@@ -0,0 +1,190 @@
+// Operations that rewrite every weight of a distribution at once.
+
+use std::{fmt::Debug, sync::Arc};
+use crate::{observer::ObserverSlot, Distribution};
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// Removes every value whose weight is below `min_weight`, returning how many were removed.
+    /// The remaining probabilities scale up to fill the freed mass.
+    pub fn truncate_below(&mut self, min_weight: f64) -> usize {
+        let removed: Vec<(T, f64)> = self.originals.iter()
+            .filter(|(_, weight)| **weight < min_weight)
+            .map(|(value, weight)| (value.clone(), *weight))
+            .collect();
+        if removed.is_empty() {
+            return 0;
+        }
+        Arc::make_mut(&mut self.originals).retain(|_, weight| *weight >= min_weight);
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in removed.iter() {
+                observer.on_remove(value, *weight);
+            }
+        }
+        removed.len()
+    }
+
+    /// Like `truncate_below()`, with the threshold given as a probability under the current weights.
+    pub fn truncate_below_probability(&mut self, min_p: f64) -> usize {
+        self.truncate_below(min_p * self.total_weight)
+    }
+
+    /// A copy whose weights are probabilities, as `normalize()` leaves them.
+    pub fn normalized(&self) -> Distribution<T> {
+        let mut result = self.clone();
+        // The copy shares the observer, which should not hear about weights it never had.
+        let observer = std::mem::replace(&mut result.observer, ObserverSlot(None));
+        result.normalize();
+        result.observer = observer;
+        result
+    }
+
+    /// Rescales the weights in place so that they are probabilities. The scale comes from a
+    /// compensated sum, so even tables mixing huge and tiny weights get the right probabilities.
+    /// As always, `total_weight` is the sum of the stored weights, which can differ from one by
+    /// a rounding error.
+    pub fn normalize(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+        let total = compensated_sum(self.originals.values().copied());
+        Arc::make_mut(&mut self.originals).values_mut().for_each(|weight| *weight /= total);
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in self.originals.iter() {
+                observer.on_add(value, *weight);
+            }
+        }
+    }
+
+    /// Raises weights below `min` to `min` and lowers weights above `max` to `max`.
+    pub fn clamp_weights(&mut self, min: f64, max: f64) {
+        assert!(min > 0.0 && min <= max);
+        let mut changed = Vec::new();
+        for (value, weight) in Arc::make_mut(&mut self.originals).iter_mut() {
+            let clamped = weight.clamp(min, max);
+            if clamped != *weight {
+                *weight = clamped;
+                changed.push((value.clone(), clamped));
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in changed.iter() {
+                observer.on_add(value, *weight);
+            }
+        }
+    }
+
+    /// Replaces every weight with `f(value, weight)`, then rebuilds once. Every new weight must
+    /// be positive and finite; they are all computed before any is stored.
+    pub fn map_weights<F:Fn(&T, f64)->f64>(&mut self, f: F) {
+        let mapped: Vec<f64> = self.originals.iter().map(|(value, weight)| f(value, *weight)).collect();
+        assert!(mapped.iter().all(|weight| weight.is_finite() && *weight > 0.0));
+        let mut changed = Vec::new();
+        for ((value, weight), new_weight) in Arc::make_mut(&mut self.originals).iter_mut().zip(mapped) {
+            if new_weight != *weight {
+                *weight = new_weight;
+                changed.push((value.clone(), new_weight));
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+        self.rebuild();
+        if let Some(observer) = &self.observer.0 {
+            for (value, weight) in changed.iter() {
+                observer.on_add(value, *weight);
+            }
+        }
+    }
+}
+
+// Neumaier's variant of Kahan summation.
+fn compensated_sum<I:Iterator<Item=f64>>(values: I) -> f64 {
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+    for value in values {
+        let next = sum + value;
+        compensation += if sum.abs() >= value.abs() {(sum - next) + value} else {(value - next) + sum};
+        sum = next;
+    }
+    sum + compensation
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::{observer::tests::EventLog, transform::compensated_sum, Distribution};
+
+    fn example() -> Distribution<char> {
+        vec![('a', 0.01), ('b', 5.0), ('c', 0.5), ('d', 4.49)].into()
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut dist = example();
+        assert_eq!(dist.truncate_below(0.5), 1);
+        assert_eq!(dist.keys().copied().collect::<String>(), "bcd");
+        assert_eq!(dist.truncate_below(0.1), 0);
+
+        let mut dist = example();
+        assert_eq!(dist.truncate_below_probability(0.06), 2);
+        assert_eq!(dist.total_weight, 9.49);
+        assert_eq!(dist.probability_vector(&['b']), vec![5.0 / 9.49]);
+    }
+
+    #[test]
+    fn test_clamp_weights() {
+        let mut dist = example();
+        dist.clamp_weights(0.1, 4.0);
+        assert_eq!(dist.weights().collect::<Vec<_>>(), vec![0.1, 4.0, 0.5, 4.0]);
+        assert_eq!(dist.total_weight, 8.6);
+        assert_eq!(dist.value_at(8.5), Some(&'d'));
+    }
+
+    #[test]
+    fn test_map_weights() {
+        let mut dist = example();
+        dist.map_weights(|value, weight| if *value == 'a' {weight * 100.0} else {weight});
+        assert_eq!(dist.weights().collect::<Vec<_>>(), vec![1.0, 5.0, 0.5, 4.49]);
+        assert_eq!(dist.total_weight, 10.99);
+
+        dist.map_weights(|_, weight| weight.sqrt());
+        assert_eq!(dist.get(&'a'), Some(1.0));
+        assert_eq!(dist.get(&'c'), Some(0.5f64.sqrt()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_map_weights_rejects_invalid() {
+        example().map_weights(|_, weight| weight - 1.0);
+    }
+
+    #[test]
+    fn test_normalized() {
+        let dist: Distribution<u32> = (0..10).map(|i| (i, 0.1)).collect::<Vec<_>>().into();
+        let normalized = dist.normalized();
+        assert!(normalized.weights().all(|weight| weight == 0.1));
+        assert_eq!(normalized.total_weight, normalized.weights().sum::<f64>());
+        assert!((normalized.total_weight - 1.0).abs() < 1e-12);
+        assert_eq!(normalized.value_at(0.9999999999), Some(&9));
+        assert_eq!(dist.total_weight, (0..10).map(|_| 0.1).sum::<f64>());
+
+        assert_eq!(compensated_sum([1e100, 1.0, -1e100].into_iter()), 1.0);
+    }
+
+    #[test]
+    fn test_normalize_notifies() {
+        let log = Arc::new(EventLog::default());
+        let mut dist: Distribution<char> = vec![('a', 1.0), ('b', 3.0)].into();
+        dist.set_observer(log.clone());
+        dist.normalized();
+        assert!(log.events().is_empty());
+        dist.normalize();
+        assert_eq!(log.events(), vec!["add a 0.25", "add b 0.75"]);
+    }
+}
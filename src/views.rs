@@ -0,0 +1,242 @@
+// Views that change which values can be picked without rebuilding the cumulative table.
+// Picks first try rejection sampling against the base table, then fall back to a single
+// renormalizing pass over the eligible values when the eligible mass is too small.
+
+use std::{fmt::Debug, collections::{BTreeMap, BTreeSet}};
+use rand::Rng;
+use crate::{pick_weighted, Distribution};
+
+const REJECTION_ATTEMPTS: usize = 32;
+
+pub(crate) fn pick_filtered<'a, T, R, F>(base: &'a Distribution<T>, rng: &mut R, keep: F) -> Option<&'a T>
+    where T: Clone + PartialEq + Eq + PartialOrd + Ord + Debug, R: Rng + ?Sized, F: Fn(&T) -> bool {
+    if base.is_empty() {
+        return None;
+    }
+    for _ in 0..REJECTION_ATTEMPTS {
+        let candidate = base.pick_ref(rng);
+        if keep(candidate) {
+            return Some(base.notify_pick(candidate));
+        }
+    }
+    pick_weighted(base.originals.iter().filter(|(value, _)| keep(value)).map(|(value, weight)| (value, *weight)), rng)
+        .map(|picked| base.notify_pick(picked))
+}
+
+pub struct ConditionalView<'a, T, F> {
+    base: &'a Distribution<T>,
+    keep: F
+}
+
+impl <'a, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, F:Fn(&T)->bool> ConditionalView<'a, T, F> {
+    pub fn is_empty(&self) -> bool {
+        !self.base.originals.keys().any(|value| (self.keep)(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.base.originals.keys().filter(|value| (self.keep)(value)).count()
+    }
+
+    /// `None` for values the condition rules out.
+    pub fn weight(&self, value: &T) -> Option<f64> {
+        self.base.get(value).filter(|_| (self.keep)(value))
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.base.originals.iter().filter(|(value, _)| (self.keep)(value)).map(|(_, weight)| weight).sum()
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        pick_filtered(self.base, rng, &self.keep)
+            .expect("no values satisfy the condition")
+            .clone()
+    }
+}
+
+pub struct Excluding<'a, T> {
+    base: &'a Distribution<T>,
+    excluded: &'a BTreeSet<T>
+}
+
+impl <'a, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Excluding<'a, T> {
+    pub fn is_empty(&self) -> bool {
+        self.base.originals.keys().all(|value| self.excluded.contains(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.base.originals.keys().filter(|value| !self.excluded.contains(value)).count()
+    }
+
+    /// `None` for excluded values.
+    pub fn weight(&self, value: &T) -> Option<f64> {
+        self.base.get(value).filter(|_| !self.excluded.contains(value))
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.base.total_weight - self.excluded.iter().filter_map(|value| self.base.originals.get(value)).sum::<f64>()
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        pick_filtered(self.base, rng, |value| !self.excluded.contains(value))
+            .expect("every value is excluded")
+            .clone()
+    }
+}
+
+pub struct Overrides<'a, T> {
+    base: &'a Distribution<T>,
+    overrides: BTreeMap<T, f64>,
+    base_remaining: f64,
+    override_total: f64
+}
+
+impl <'a, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Overrides<'a, T> {
+    pub fn weight(&self, value: &T) -> Option<f64> {
+        self.overrides.get(value).or_else(|| self.base.originals.get(value)).copied()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.base_remaining + self.override_total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_weight() == 0.0
+    }
+
+    /// The number of values with a positive weight in this view.
+    pub fn len(&self) -> usize {
+        let base = self.base.originals.keys().filter(|value| !self.overrides.contains_key(value)).count();
+        base + self.overrides.values().filter(|weight| **weight > 0.0).count()
+    }
+
+    pub fn random_pick(&self) -> T {
+        self.pick_with(&mut rand::thread_rng())
+    }
+
+    pub fn pick_with<R:Rng + ?Sized>(&self, rng: &mut R) -> T {
+        assert!(!self.is_empty());
+        if rng.gen_range(0.0..self.total_weight()) < self.override_total {
+            self.pick_override(rng)
+        } else {
+            pick_filtered(self.base, rng, |value| !self.overrides.contains_key(value))
+                .expect("a positive base_remaining leaves some value unoverridden")
+                .clone()
+        }
+    }
+
+    fn pick_override<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        let picked = pick_weighted(self.overrides.iter().map(|(value, weight)| (value, *weight)), rng).unwrap();
+        self.base.notify_pick(picked).clone()
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> Distribution<T> {
+    /// A view restricted to values satisfying `keep`, renormalized at pick time. Unlike
+    /// `distro_with()`, nothing is copied, so this suits short-lived conditions.
+    pub fn conditioned<F:Fn(&T)->bool>(&self, keep: F) -> ConditionalView<'_, T, F> {
+        ConditionalView {base: self, keep}
+    }
+
+    /// A view that never picks values in `excluded`, redistributing their mass over the rest.
+    pub fn excluding<'a>(&'a self, excluded: &'a BTreeSet<T>) -> Excluding<'a, T> {
+        Excluding {base: self, excluded}
+    }
+
+    /// A view in which each value in `overrides` uses the given weight instead of its own.
+    /// Values absent from this distribution are added for the lifetime of the view; a weight
+    /// of zero disables a value.
+    pub fn with_overrides(&self, overrides: BTreeMap<T, f64>) -> Overrides<'_, T> {
+        assert!(overrides.values().all(|w| w.is_finite() && *w >= 0.0));
+        // Summed directly rather than subtracted from the total, so that overriding every value
+        // leaves exactly zero instead of a rounding residue.
+        let base_remaining = self.originals.iter().filter(|(value, _)| !overrides.contains_key(value)).map(|(_, weight)| weight).sum();
+        let override_total = overrides.values().sum();
+        Overrides {base: self, overrides, base_remaining, override_total}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use crate::Distribution;
+
+    fn example() -> Distribution<char> {
+        let mut dist = Distribution::new();
+        dist.add(&'a', 1.0);
+        dist.add(&'b', 2.0);
+        dist.add(&'c', 1000.0);
+        dist
+    }
+
+    #[test]
+    fn test_conditioned() {
+        let dist = example();
+        let view = dist.conditioned(|c| *c != 'c');
+        assert!(!view.is_empty());
+        assert_eq!(view.total_weight(), 3.0);
+        let b_count = (0..300).filter(|_| {
+            let picked = view.random_pick();
+            assert_ne!(picked, 'c');
+            picked == 'b'
+        }).count();
+        assert!(b_count > 150);
+        assert!(dist.conditioned(|c| *c == 'z').is_empty());
+    }
+
+    #[test]
+    fn test_excluding() {
+        let dist = example();
+        let mut excluded = BTreeSet::new();
+        excluded.insert('c');
+        excluded.insert('z');
+        let view = dist.excluding(&excluded);
+        assert_eq!(view.total_weight(), 3.0);
+        assert!((0..100).all(|_| view.random_pick() != 'c'));
+
+        excluded.insert('a');
+        excluded.insert('b');
+        assert!(dist.excluding(&excluded).is_empty());
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let dist = example();
+        let mut overrides = BTreeMap::new();
+        overrides.insert('c', 0.0);
+        overrides.insert('d', 3.0);
+        let view = dist.with_overrides(overrides);
+        assert_eq!(view.weight(&'a'), Some(1.0));
+        assert_eq!(view.weight(&'c'), Some(0.0));
+        assert_eq!(view.weight(&'d'), Some(3.0));
+        assert_eq!(view.total_weight(), 6.0);
+
+        let d_count = (0..300).filter(|_| {
+            let picked = view.random_pick();
+            assert_ne!(picked, 'c');
+            picked == 'd'
+        }).count();
+        assert!(d_count > 100);
+        assert_eq!(dist.originals[&'c'], 1000.0);
+    }
+
+    #[test]
+    fn test_overriding_every_value() {
+        let dist: Distribution<char> = vec![('a', 1.0), ('b', 4.0), ('c', 1.0)].into();
+        let zeroed: BTreeMap<char, f64> = dist.originals.keys().map(|value| (*value, 0.0)).collect();
+        assert!(dist.with_overrides(zeroed.clone()).is_empty());
+
+        let mut replaced = zeroed;
+        replaced.insert('d', 1.0);
+        let view = dist.with_overrides(replaced);
+        assert_eq!(view.total_weight(), 1.0);
+        assert!((0..100).all(|_| view.random_pick() == 'd'));
+    }
+}
@@ -0,0 +1,560 @@
+// A dyn-compatible view of the samplers in this crate. Each sampler keeps its own pick
+// methods; these impls only route them through a `&mut dyn RngCore`.
+
+use std::{fmt::Debug, hash::Hash, time::Instant};
+use rand::RngCore;
+use crate::{Annealed, ApproxDistribution, BetaWeighted, ConditionalView, DeterministicDistribution, Distribution, DynamicDistribution, Excluding, HealthTracker, IndexDistribution, Instrumented, Journaled, NonRepeatingSampler, Overrides, PacedSampler, PickSink, PityDistribution, RangeDistribution, RecencySampler, Recorder, Schedule, SmallDistribution, SoftmaxSampler, StaticDistribution, StaticTable, StockedDistribution, TieredDistribution, TimeWeighted};
+#[cfg(feature = "mmap")]
+use crate::{ByteValue, MmapDistribution};
+
+/// Picking from any sampler behind a `Box<dyn WeightedSelect<T>>`. `pick` takes `&mut self`
+/// so that samplers that use up values, such as `StockedDistribution`, can take part.
+///
+/// Some samplers are left out because their picks do not fit this signature:
+/// `ContextualDistribution` and `MarkovChain` need a context or a current state for each pick,
+/// `LootTable` yields several items per roll, `IntervalDistribution` and `PiecewiseLinear`
+/// draw from continuous ranges with densities rather than weights, `ConsistentSelector` maps
+/// keys rather than drawing at random, `WeightedRoundRobin` and `DeficitRoundRobin` follow a
+/// fixed schedule, and `SamplerHandle` picks on its worker thread, with its own generator.
+pub trait WeightedSelect<T> {
+    /// Returns `None` if nothing can be picked.
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T>;
+
+    /// The number of values that can currently be picked.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The weight `value` is currently picked with, in the sampler's own units, so weights
+    /// from one sampler are comparable only with each other. `None` for values it cannot pick.
+    fn weight_of(&self, value: &T) -> Option<f64>;
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for Distribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!Distribution::is_empty(self)).then(|| self.notify_pick(self.pick_ref(rng)).clone())
+    }
+
+    fn len(&self) -> usize {
+        self.originals.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.get(value)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug + Hash> WeightedSelect<T> for ApproxDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!ApproxDistribution::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.candidates().count()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.candidates().any(|c| c == value).then(|| self.estimate(value))
+    }
+}
+
+/// Weights are the probabilities of each value across both stages.
+impl <G:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for TieredDistribution<G, T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!TieredDistribution::is_empty(self)).then(|| self.pick_ref(rng).clone())
+    }
+
+    fn len(&self) -> usize {
+        self.active_tiers().map(|(tier, _)| self.tier(tier).unwrap().keys().count()).sum()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        let probabilities = self.tier_probabilities();
+        let p: f64 = probabilities.iter()
+            .filter_map(|(tier, p)| self.tier(tier).unwrap().probabilities().find(|(v, _)| *v == value).map(|(_, q)| p * q))
+            .sum();
+        (p > 0.0).then_some(p)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for StockedDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        self.pick_with(rng)
+    }
+
+    fn len(&self) -> usize {
+        self.available.keys().count()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.available.get(value)
+    }
+}
+
+impl WeightedSelect<usize> for IndexDistribution {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<usize> {
+        Some(self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        IndexDistribution::len(self)
+    }
+
+    fn weight_of(&self, index: &usize) -> Option<f64> {
+        self.weight(*index).filter(|weight| *weight > 0.0)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for DynamicDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        self.pick_with(rng)
+    }
+
+    fn len(&self) -> usize {
+        DynamicDistribution::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.weight(value).filter(|weight| *weight > 0.0)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for SmallDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!SmallDistribution::is_empty(self)).then(|| self.pick_with(rng).clone())
+    }
+
+    fn len(&self) -> usize {
+        SmallDistribution::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.get(value)
+    }
+}
+
+impl <T:Clone + PartialEq> WeightedSelect<T> for StaticTable<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        Some(self.pick_with(rng).clone())
+    }
+
+    fn len(&self) -> usize {
+        StaticTable::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.values().iter().position(|v| v == value).and_then(|index| self.weight_at(index))
+    }
+}
+
+impl <T:Clone + PartialEq, const N: usize> WeightedSelect<T> for StaticDistribution<'_, T, N> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!StaticDistribution::is_empty(self)).then(|| self.pick_with(rng).clone())
+    }
+
+    fn len(&self) -> usize {
+        StaticDistribution::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.pairs().iter().find(|(v, _)| v == value).map(|(_, weight)| *weight)
+    }
+}
+
+/// Weights are in the same integer units as `DeterministicDistribution::weight()`. Picks made
+/// through this trait are only reproducible across platforms if the generator is.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for DeterministicDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!DeterministicDistribution::is_empty(self)).then(|| self.pick_with(rng).clone())
+    }
+
+    fn len(&self) -> usize {
+        DeterministicDistribution::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.weight(value).map(|weight| weight as f64)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl <T:ByteValue + Ord> WeightedSelect<T> for MmapDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!MmapDistribution::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        MmapDistribution::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.get(value)
+    }
+}
+
+/// Weights are those at the current temperature; a value listed more than once counts with
+/// the sum of its weights.
+impl <T:Clone + PartialEq> WeightedSelect<T> for SoftmaxSampler<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        self.weights().next().is_some().then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        let mut pickable: Vec<&T> = Vec::new();
+        for (value, _) in self.weights().filter(|(_, weight)| *weight > 0.0) {
+            if !pickable.contains(&value) {
+                pickable.push(value);
+            }
+        }
+        pickable.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        let weight: f64 = self.weights().filter(|(v, _)| *v == value).map(|(_, weight)| weight).sum();
+        (weight > 0.0).then_some(weight)
+    }
+}
+
+/// Weights are decayed to the moment `weight_of()` is called.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for TimeWeighted<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!TimeWeighted::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.pickable_len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.pickable_weight_at(value, Instant::now())
+    }
+}
+
+/// Each pick through this trait counts toward the pity rules, as `pick()` does.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for PityDistribution<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!self.distribution().is_empty()).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().keys().filter(|value| self.weight(value).is_some()).count()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.weight(value)
+    }
+}
+
+/// Picks one integer at a time, so `len()` counts the distinct integers covered, saturating at
+/// `usize::MAX`, and `weight_of()` gives the weight of a single integer.
+impl WeightedSelect<u64> for RangeDistribution {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<u64> {
+        (!RangeDistribution::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        usize::try_from(self.integer_count()).unwrap_or(usize::MAX)
+    }
+
+    fn weight_of(&self, value: &u64) -> Option<f64> {
+        self.weight(*value)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, F:Fn(&T)->bool> WeightedSelect<T> for ConditionalView<'_, T, F> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!ConditionalView::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        ConditionalView::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.weight(value)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for Excluding<'_, T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!Excluding::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        Excluding::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.weight(value)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for Overrides<'_, T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!Overrides::is_empty(self)).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        Overrides::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.weight(value).filter(|weight| *weight > 0.0)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for Instrumented<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!self.distribution().is_empty()).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().originals.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.distribution().get(value)
+    }
+}
+
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for Journaled<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        let dist = self.distribution();
+        (!dist.is_empty()).then(|| dist.notify_pick(dist.pick_ref(rng)).clone())
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().originals.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.distribution().get(value)
+    }
+}
+
+/// Each pick through this trait advances the schedule, and weights are those at the current
+/// temperature, scaled so that the heaviest value has weight one.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, S:Schedule> WeightedSelect<T> for Annealed<T, S> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!self.distribution().is_empty()).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().keys().filter(|value| self.tempered_weight(value).is_some()).count()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.tempered_weight(value)
+    }
+}
+
+/// Weights include the current recency penalties.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for RecencySampler<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!self.distribution().is_empty()).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().originals.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.distribution().get(value).map(|weight| weight * self.multiplier(value))
+    }
+}
+
+/// The last pick cannot be picked again unless it is the only value.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for NonRepeatingSampler<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!self.distribution().is_empty()).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        let len = self.distribution().originals.len();
+        if len > 1 && self.last().is_some_and(|last| self.distribution().get(last).is_some()) {len - 1} else {len}
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        let blocked = self.last() == Some(value) && self.distribution().originals.len() > 1;
+        self.distribution().get(value).filter(|_| !blocked)
+    }
+}
+
+/// Each pick draws a fresh weight for every value from its posterior and picks by those, as in
+/// Thompson sampling, so `weight_of()` gives the posterior mean success rate.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for BetaWeighted<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        let sampled = self.sample_distribution_with(rng);
+        (!sampled.is_empty()).then(|| sampled.pick_ref(rng).clone())
+    }
+
+    fn len(&self) -> usize {
+        BetaWeighted::len(self)
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.counts(value).map(|(successes, failures)| (successes as f64 + 1.0) / ((successes + failures) as f64 + 2.0))
+    }
+}
+
+/// Every pick through this trait is logged to the sink, as `random_pick()` does.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug, S:PickSink<T>> WeightedSelect<T> for Recorder<'_, T, S> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        (!self.distribution().is_empty()).then(|| self.pick_with(rng))
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().originals.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.distribution().get(value)
+    }
+}
+
+/// Health is judged at the moment each method is called.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for HealthTracker<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        self.random_pick_at_with(Instant::now(), rng)
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().keys().filter(|value| self.is_healthy(value)).count()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.distribution().get(value).filter(|_| self.is_healthy(value))
+    }
+}
+
+/// `pick()` never waits: it returns `None` until the next pick is due, as `try_pick_at()` does.
+impl <T:Clone + PartialEq + Eq + PartialOrd + Ord + Debug> WeightedSelect<T> for PacedSampler<T> {
+    fn pick(&mut self, rng: &mut dyn RngCore) -> Option<T> {
+        self.try_pick_at_with(Instant::now(), rng)
+    }
+
+    fn len(&self) -> usize {
+        self.distribution().originals.len()
+    }
+
+    fn weight_of(&self, value: &T) -> Option<f64> {
+        self.distribution().get(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::time::Duration;
+    use crate::{Annealed, BetaWeighted, DeterministicDistribution, Distribution, ExponentialSchedule, HealthTracker, IndexDistribution, Instrumented, Journaled, NonRepeatingSampler, PacedSampler, PityDistribution, PityRule, RangeDistribution, RecencySampler, SoftmaxSampler, SplitMix64, StockedDistribution, TieredDistribution, WeightedSelect};
+
+    #[test]
+    fn test_heterogeneous_samplers() {
+        let plain: Distribution<usize> = vec![(0, 1.0), (1, 3.0)].into();
+        let mut stocked = StockedDistribution::new();
+        stocked.add(&0, 1.0, 1);
+        let mut tiered = TieredDistribution::new();
+        tiered.set_tier_weight(&"common", 3.0);
+        tiered.set_tier_weight(&"rare", 1.0);
+        tiered.add_tiered(&0, &"common", 1.0);
+        tiered.add_tiered(&1, &"rare", 1.0);
+
+        let mut samplers: Vec<Box<dyn WeightedSelect<usize>>> = vec![
+            Box::new(plain), Box::new(stocked), Box::new(tiered), Box::new(IndexDistribution::new(&[0.0, 2.0]))
+        ];
+        assert_eq!(samplers.iter().map(|s| s.len()).collect::<Vec<_>>(), vec![2, 1, 2, 2]);
+        assert_eq!(samplers[2].weight_of(&1), Some(0.25));
+        assert_eq!(samplers[3].weight_of(&0), None);
+
+        let mut rng = SplitMix64::new(3);
+        for sampler in samplers.iter_mut() {
+            let picked = sampler.pick(&mut rng).unwrap();
+            assert!(sampler.weight_of(&picked).is_some() || sampler.is_empty());
+        }
+        assert_eq!(samplers[1].pick(&mut rng), None);
+        assert_eq!(samplers[3].pick(&mut rng), Some(1));
+    }
+
+    #[test]
+    fn test_more_samplers() {
+        let mut deterministic = DeterministicDistribution::new();
+        deterministic.add(&0, 3);
+        let mut pity = PityDistribution::new(vec![(0, 1000.0), (1, 0.001)].into());
+        pity.add_rule(&1, PityRule::hard(2));
+        let mut samplers: Vec<Box<dyn WeightedSelect<u64>>> = vec![
+            Box::new(deterministic),
+            Box::new(SoftmaxSampler::new(vec![(0, 0.0), (1, 0.0), (0, 0.0)], 1.0)),
+            Box::new(pity),
+            Box::new([(0..10, 1.0), (5..20, 3.0)].into_iter().collect::<RangeDistribution>())
+        ];
+        assert_eq!(samplers.iter().map(|s| s.len()).collect::<Vec<_>>(), vec![1, 2, 2, 20]);
+        assert_eq!(samplers[0].weight_of(&0), Some(3.0));
+        assert_eq!(samplers[1].weight_of(&0), Some(2.0));
+        assert_eq!(samplers[3].weight_of(&7), Some(0.1 + 0.2));
+        assert_eq!(samplers[3].weight_of(&20), None);
+
+        let mut rng = SplitMix64::new(9);
+        assert_eq!(samplers[0].pick(&mut rng), Some(0));
+        samplers[2].pick(&mut rng);
+        assert_eq!(samplers[2].weight_of(&0), None);
+        assert_eq!(samplers[2].pick(&mut rng), Some(1));
+        assert!(samplers[3].pick(&mut rng).unwrap() < 20);
+    }
+
+    #[test]
+    fn test_views() {
+        let dist: Distribution<char> = vec![('a', 1.0), ('b', 2.0), ('c', 3.0)].into();
+        let excluded: BTreeSet<char> = ['b'].into_iter().collect();
+        let overrides: BTreeMap<char, f64> = [('a', 0.0), ('d', 4.0)].into_iter().collect();
+        let mut views: Vec<Box<dyn WeightedSelect<char>>> = vec![
+            Box::new(dist.conditioned(|c| *c != 'c')), Box::new(dist.excluding(&excluded)), Box::new(dist.with_overrides(overrides))
+        ];
+        assert_eq!(views.iter().map(|v| v.len()).collect::<Vec<_>>(), vec![2, 2, 3]);
+        assert_eq!(views[0].weight_of(&'c'), None);
+        assert_eq!(views[1].weight_of(&'b'), None);
+        assert_eq!(views[2].weight_of(&'a'), None);
+        assert_eq!(views[2].weight_of(&'d'), Some(4.0));
+
+        let mut rng = SplitMix64::new(6);
+        for view in views.iter_mut() {
+            let picked = view.pick(&mut rng).unwrap();
+            assert!(view.weight_of(&picked).is_some());
+        }
+    }
+
+    #[test]
+    fn test_stateful_samplers() {
+        let dist: Distribution<char> = vec![('a', 1.0), ('b', 4.0)].into();
+        let mut beta = BetaWeighted::new();
+        beta.record(&'a', true);
+        beta.record(&'b', false);
+        let mut health = HealthTracker::new(dist.clone());
+        health.mark_down(&'b', Duration::from_secs(3600));
+        let mut samplers: Vec<Box<dyn WeightedSelect<char>>> = vec![
+            Box::new(Instrumented::new(dist.clone())),
+            Box::new(Journaled::new(dist.clone())),
+            Box::new(Annealed::new(dist.clone(), ExponentialSchedule {initial: 1.0, rate: 0.5})),
+            Box::new(RecencySampler::new(dist.clone(), 2, 0.5)),
+            Box::new(NonRepeatingSampler::new(dist.clone())),
+            Box::new(beta),
+            Box::new(health),
+            Box::new(PacedSampler::new(dist, 1e-3))
+        ];
+        assert_eq!(samplers.iter().map(|s| s.len()).collect::<Vec<_>>(), vec![2, 2, 2, 2, 2, 2, 1, 2]);
+        assert_eq!(samplers[2].weight_of(&'b'), Some(1.0));
+        assert_eq!(samplers[2].weight_of(&'a'), Some(0.25));
+        assert_eq!(samplers[5].weight_of(&'a'), Some(2.0 / 3.0));
+        assert_eq!(samplers[6].weight_of(&'b'), None);
+
+        let mut rng = SplitMix64::new(4);
+        let picks: Vec<char> = samplers.iter_mut().map(|s| s.pick(&mut rng).unwrap()).collect();
+        assert_eq!(picks[6], 'a');
+        assert_eq!(samplers[7].pick(&mut rng), None);
+        assert_eq!(samplers[4].weight_of(&picks[4]), None);
+        assert_eq!(samplers[4].len(), 1);
+        assert!(samplers[3].weight_of(&picks[3]).unwrap() < samplers[0].weight_of(&picks[3]).unwrap());
+        assert!(samplers[2].weight_of(&'a').unwrap() < 0.25);
+    }
+}